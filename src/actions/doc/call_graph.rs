@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use regex::Regex;
+
+use crate::actions::function_signature::unquote_ident;
+
+// A function (covering all of its overloads, as calls can't be matched to a single overload without
+// knowing the types of the arguments)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FunctionRef {
+    pub schema: String,
+    pub name: String,
+}
+
+impl std::fmt::Display for FunctionRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.schema, self.name)
+    }
+}
+
+// Remove comments and single quoted strings so that calls within them aren't counted. Dollar
+// quotes are kept as they contain the function body
+pub fn strip_comments_and_strings(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        stripped.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // Block comments can be nested
+                let mut depth = 1;
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    // Reset prev after a marker so that its last character can't start another
+                    match (prev, c) {
+                        ('/', '*') => (depth, prev) = (depth + 1, ' '),
+                        ('*', '/') => (depth, prev) = (depth - 1, ' '),
+                        _ => prev = c,
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                stripped.push(' ');
+            }
+            '\'' => {
+                // An escaped '' is treated as the end of one string and the start of the next
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                }
+                stripped.push(' ');
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+// Which local functions call which, found by searching the function bodies for calls
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    calls: BTreeMap<FunctionRef, BTreeSet<FunctionRef>>,
+    called_by: BTreeMap<FunctionRef, BTreeSet<FunctionRef>>,
+}
+
+impl CallGraph {
+    // Build the graph from the definition of each overload. Unqualified calls are resolved to the
+    // schema of the calling function first, then to public
+    pub fn build(definitions: &[(FunctionRef, String)]) -> Self {
+        let call_regex =
+            Regex::new(r#"(?:("[^"]+"|[A-Za-z_][\w$]*)\s*\.\s*)?("[^"]+"|[A-Za-z_][\w$]*)\s*\("#)
+                .expect("The call regex should be valid");
+        let known: BTreeSet<&FunctionRef> = definitions.iter().map(|(function, _)| function).collect();
+
+        let mut graph = Self::default();
+        for (caller, definition) in definitions {
+            for captures in call_regex.captures_iter(&strip_comments_and_strings(definition)) {
+                let name = unquote_ident(&captures[2]);
+                let candidates = match captures.get(1) {
+                    Some(schema) => vec![unquote_ident(schema.as_str())],
+                    None => vec![caller.schema.clone(), String::from("public")],
+                };
+
+                let callee = candidates
+                    .into_iter()
+                    .map(|schema| FunctionRef {
+                        schema,
+                        name: name.clone(),
+                    })
+                    .find(|callee| known.contains(callee));
+
+                // The declaration of the function always matches itself, so recursion is ignored
+                if let Some(callee) = callee.filter(|callee| callee != caller) {
+                    graph.add_call(caller.clone(), callee);
+                }
+            }
+        }
+
+        graph
+    }
+
+    fn add_call(&mut self, caller: FunctionRef, callee: FunctionRef) {
+        self.called_by
+            .entry(callee.clone())
+            .or_default()
+            .insert(caller.clone());
+        self.calls.entry(caller).or_default().insert(callee);
+    }
+
+    pub fn calls(&self, function: &FunctionRef) -> Vec<&FunctionRef> {
+        self.calls
+            .get(function)
+            .map(|callees| callees.iter().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn called_by(&self, function: &FunctionRef) -> Vec<&FunctionRef> {
+        self.called_by
+            .get(function)
+            .map(|callers| callers.iter().collect())
+            .unwrap_or_default()
+    }
+
+    // Render every call made to or from a function of the schema as a mermaid flowchart
+    pub fn to_mermaid(&self, schema: &str) -> String {
+        let edges: Vec<(&FunctionRef, &FunctionRef)> = self
+            .calls
+            .iter()
+            .flat_map(|(caller, callees)| callees.iter().map(move |callee| (caller, callee)))
+            .filter(|(caller, callee)| caller.schema == schema || callee.schema == schema)
+            .collect();
+
+        let mut node_ids: BTreeMap<&FunctionRef, usize> = BTreeMap::new();
+        for (caller, callee) in &edges {
+            let next_id = node_ids.len();
+            node_ids.entry(caller).or_insert(next_id);
+            let next_id = node_ids.len();
+            node_ids.entry(callee).or_insert(next_id);
+        }
+
+        let mut nodes: Vec<(&FunctionRef, usize)> = node_ids.iter().map(|(f, id)| (*f, *id)).collect();
+        nodes.sort_by_key(|(_, id)| *id);
+
+        let mut mermaid = String::from("flowchart LR\n");
+        for (function, id) in nodes {
+            mermaid.push_str(&format!(
+                "    n{}[\"{}\"]\n",
+                id,
+                function.to_string().replace('"', "#quot;")
+            ));
+        }
+        for (caller, callee) in edges {
+            mermaid.push_str(&format!("    n{} --> n{}\n", node_ids[caller], node_ids[callee]));
+        }
+
+        mermaid
+    }
+
+    // The call graph section of the schema's README. It is part of the README rather than its own
+    // file as any other file name could also be the name of a function's docs
+    pub fn to_markdown(&self, schema: &str) -> String {
+        let mermaid = self.to_mermaid(schema);
+        let mut section = String::from("## Call Graph\n\n");
+        if mermaid.lines().count() == 1 {
+            section.push_str("None of the functions in this schema call another local function.\n");
+        } else {
+            section.push_str(&format!("```mermaid\n{}```\n", mermaid));
+        }
+
+        section
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function_ref(schema: &str, name: &str) -> FunctionRef {
+        FunctionRef {
+            schema: schema.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn strip_comments_and_strings_works() {
+        assert_eq!(
+            strip_comments_and_strings("SELECT a(); -- b()\n/* c() /* d() **/ e() */ f('g()', 'it''s')"),
+            "SELECT a(); \n  f( ,   )"
+        );
+    }
+
+    #[test]
+    fn build_works() {
+        let definitions = vec![
+            (
+                function_ref("public", "outer_func"),
+                String::from(
+                    r#"CREATE OR REPLACE FUNCTION public.outer_func(x integer)
+ RETURNS integer
+ LANGUAGE plpgsql
+AS $function$
+BEGIN
+    -- helper(x) is not called here
+    RAISE NOTICE 'helper(%)', x;
+    PERFORM audit."log"(x);
+    RETURN helper(x) + outer_func(x - 1);
+END
+$function$"#,
+                ),
+            ),
+            (
+                function_ref("public", "helper"),
+                String::from("CREATE FUNCTION public.helper(x integer) RETURNS integer AS $$ SELECT x $$"),
+            ),
+            (
+                function_ref("audit", "log"),
+                String::from("CREATE FUNCTION audit.log(x integer) RETURNS void AS $$ SELECT public.helper(x) $$"),
+            ),
+        ];
+
+        let graph = CallGraph::build(&definitions);
+
+        assert_eq!(
+            graph.calls(&function_ref("public", "outer_func")),
+            vec![&function_ref("audit", "log"), &function_ref("public", "helper")]
+        );
+        assert_eq!(
+            graph.called_by(&function_ref("public", "helper")),
+            vec![&function_ref("audit", "log"), &function_ref("public", "outer_func")]
+        );
+        assert!(graph.calls(&function_ref("public", "helper")).is_empty());
+
+        assert_eq!(
+            graph.to_mermaid("audit"),
+            r#"flowchart LR
+    n0["audit.log"]
+    n1["public.helper"]
+    n2["public.outer_func"]
+    n0 --> n1
+    n2 --> n0
+"#
+        );
+        assert!(graph
+            .to_markdown("audit")
+            .starts_with("## Call Graph\n\n```mermaid\nflowchart LR\n"));
+        assert!(graph
+            .to_markdown("empty")
+            .contains("None of the functions in this schema call another local function."));
+    }
+}
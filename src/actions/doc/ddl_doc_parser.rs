@@ -0,0 +1,919 @@
+use anyhow::{Context, Result};
+
+use crate::actions::function_signature::{
+    find_closing_bracket, split_top_level, split_words, unquote_ident,
+};
+
+// Words that end the type of a column or domain and begin its options
+const COLUMN_OPTION_KEYWORDS: [&str; 10] = [
+    "NOT",
+    "NULL",
+    "DEFAULT",
+    "CONSTRAINT",
+    "COLLATE",
+    "GENERATED",
+    "CHECK",
+    "REFERENCES",
+    "PRIMARY",
+    "UNIQUE",
+];
+
+// Words that begin a table level constraint within a CREATE TABLE statement
+const TABLE_CONSTRAINT_KEYWORDS: [&str; 7] = [
+    "CONSTRAINT",
+    "PRIMARY",
+    "UNIQUE",
+    "CHECK",
+    "FOREIGN",
+    "EXCLUDE",
+    "LIKE",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationKind {
+    Table,
+    View,
+    MaterializedView,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnDoc {
+    pub name: String,
+    pub column_type: String, // Empty for views as the type is not part of their DDL
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub description: Option<String>,
+}
+
+// A named item such as a constraint or an index along with its definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedDefinition {
+    pub name: String,
+    pub definition: String,
+}
+
+// The documentation of a table or view parsed from the pg_dump output pulled into the repo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationDoc {
+    pub schema: String,
+    pub name: String,
+    pub kind: RelationKind,
+    pub description: Option<String>, // From COMMENT ON TABLE/VIEW
+    pub columns: Vec<ColumnDoc>,
+    pub constraints: Vec<NamedDefinition>,
+    pub indexes: Vec<NamedDefinition>,
+    pub query: Option<String>, // The query of a view
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataTypeKind {
+    Enum {
+        labels: Vec<String>,
+    },
+    Domain {
+        base_type: String,
+        nullable: bool,
+        default: Option<String>,
+        constraints: Vec<NamedDefinition>,
+    },
+    Composite {
+        attributes: Vec<ColumnDoc>,
+    },
+}
+
+// The documentation of a data type parsed from the definitions pulled into the repo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataTypeDoc {
+    pub schema: String,
+    pub name: String,
+    pub kind: DataTypeKind,
+    pub description: Option<String>, // From COMMENT ON TYPE/DOMAIN
+}
+
+impl RelationDoc {
+    // Parse a table or view from its pg_dump output. Returns None if the file does not create one
+    pub fn from_pg_dump(schema: &str, name: &str, ddl: &str) -> Result<Option<Self>> {
+        let mut relation: Option<Self> = None;
+        let mut comments = vec![];
+        let mut alterations = vec![];
+
+        for statement in split_statements(ddl) {
+            let words = split_words(&statement);
+            let upper_words: Vec<String> = words.iter().map(|w| w.to_uppercase()).collect();
+            let upper_words: Vec<&str> = upper_words.iter().map(String::as_str).collect();
+
+            let is_create = upper_words.first() == Some(&"CREATE");
+            let is_index = upper_words.contains(&"INDEX");
+
+            if is_create && !is_index && upper_words.contains(&"TABLE") {
+                let mut table = Self {
+                    schema: schema.to_string(),
+                    name: name.to_string(),
+                    kind: RelationKind::Table,
+                    description: None,
+                    columns: vec![],
+                    constraints: vec![],
+                    indexes: vec![],
+                    query: None,
+                };
+                table
+                    .parse_table_body(&statement, &upper_words)
+                    .context(format!("Could not parse the table definition of {}", name))?;
+                relation = Some(table);
+            } else if is_create && !is_index && upper_words.contains(&"VIEW") {
+                relation = Some(Self::parse_view(
+                    schema,
+                    name,
+                    &statement,
+                    &words,
+                    &upper_words,
+                ));
+            } else if upper_words.starts_with(&["COMMENT", "ON"]) {
+                comments.push(statement);
+            } else if upper_words.starts_with(&["ALTER", "TABLE"]) || (is_create && is_index) {
+                alterations.push(statement);
+            }
+        }
+
+        let mut relation = match relation {
+            Some(relation) => relation,
+            None => return Ok(None),
+        };
+
+        for statement in &alterations {
+            relation.apply_alteration(statement);
+        }
+
+        for statement in &comments {
+            if let Some(comment) = parse_comment(statement) {
+                match comment.object_type.as_str() {
+                    "TABLE" | "VIEW" | "MATERIALIZED VIEW" => relation.description = comment.text,
+                    "COLUMN" => {
+                        if let Some(column) = relation
+                            .columns
+                            .iter_mut()
+                            .find(|column| Some(&column.name) == comment.target.last())
+                        {
+                            column.description = comment.text;
+                        }
+                    }
+                    _ => continue,
+                }
+            }
+        }
+
+        Ok(Some(relation))
+    }
+
+    fn parse_table_body(&mut self, statement: &str, upper_words: &[&str]) -> Result<()> {
+        // Partitions inherit their columns from the parent table
+        if upper_words.windows(2).any(|w| w == ["PARTITION", "OF"]) {
+            return Ok(());
+        }
+
+        let body_start = statement
+            .find('(')
+            .context("The CREATE TABLE statement has no column list")?;
+        let body_end = find_closing_bracket(statement, body_start)
+            .context("The column list of the CREATE TABLE statement is never closed")?;
+
+        for item in split_top_level(&statement[body_start + 1..body_end], ',') {
+            let words = split_words(item.trim());
+            let first_word = match words.first() {
+                Some(word) => word.to_uppercase(),
+                None => continue,
+            };
+
+            if TABLE_CONSTRAINT_KEYWORDS.contains(&first_word.as_str()) {
+                let (name, definition) = if first_word == "CONSTRAINT" && words.len() > 2 {
+                    (unquote_ident(words[1]), rest_of(item, words[2]))
+                } else {
+                    (String::new(), item.trim().to_string())
+                };
+                self.constraints.push(NamedDefinition { name, definition });
+                continue;
+            }
+
+            let type_end = words
+                .iter()
+                .skip(1)
+                .position(|word| COLUMN_OPTION_KEYWORDS.contains(&word.to_uppercase().as_str()))
+                .map(|pos| pos + 1)
+                .unwrap_or(words.len());
+
+            let (nullable, default, _) = parse_column_options(&words[type_end..]);
+            self.columns.push(ColumnDoc {
+                name: unquote_ident(words[0]),
+                column_type: words[1..type_end].join(" "),
+                nullable,
+                default,
+                description: None,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn parse_view(
+        schema: &str,
+        name: &str,
+        statement: &str,
+        words: &[&str],
+        upper_words: &[&str],
+    ) -> Self {
+        let kind = if upper_words.contains(&"MATERIALIZED") {
+            RelationKind::MaterializedView
+        } else {
+            RelationKind::View
+        };
+
+        let view_pos = upper_words
+            .iter()
+            .position(|w| *w == "VIEW")
+            .unwrap_or_default();
+        let as_pos = upper_words
+            .iter()
+            .skip(view_pos)
+            .position(|w| *w == "AS")
+            .map(|pos| pos + view_pos);
+
+        let query = as_pos
+            .and_then(|as_pos| words.get(as_pos + 1))
+            .map(|first_word| {
+                let query = rest_of(statement, first_word);
+                let upper_query = query.to_uppercase();
+                match upper_query
+                    .strip_suffix("WITH NO DATA")
+                    .or_else(|| upper_query.strip_suffix("WITH DATA"))
+                {
+                    Some(stripped) => query[..stripped.len()].trim().to_string(),
+                    None => query,
+                }
+            });
+
+        // Use the explicit column list if there is one, otherwise the outputs of the query
+        let column_names = match (as_pos, words.get(view_pos + 2)) {
+            (Some(as_pos), Some(column_list))
+                if as_pos > view_pos + 2 && column_list.starts_with('(') =>
+            {
+                split_top_level(&column_list[1..column_list.len() - 1], ',')
+                    .into_iter()
+                    .map(unquote_ident)
+                    .collect()
+            }
+            _ => query
+                .as_deref()
+                .map(get_select_output_names)
+                .unwrap_or_default(),
+        };
+
+        Self {
+            schema: schema.to_string(),
+            name: name.to_string(),
+            kind,
+            description: None,
+            columns: column_names
+                .into_iter()
+                .map(|name| ColumnDoc {
+                    name,
+                    nullable: true,
+                    ..Default::default()
+                })
+                .collect(),
+            constraints: vec![],
+            indexes: vec![],
+            query,
+        }
+    }
+
+    // Apply the ALTER TABLE and CREATE INDEX statements that pg_dump outputs after the table
+    fn apply_alteration(&mut self, statement: &str) {
+        let words = split_words(statement);
+        let upper_words: Vec<String> = words.iter().map(|w| w.to_uppercase()).collect();
+
+        if upper_words.contains(&String::from("INDEX")) {
+            if let Some(on_pos) = upper_words.iter().position(|w| w == "ON") {
+                self.indexes.push(NamedDefinition {
+                    name: unquote_ident(words[on_pos - 1]),
+                    definition: format!("{};", statement.trim()),
+                });
+            }
+            return;
+        }
+
+        // Skip over ALTER TABLE [ONLY] [IF EXISTS] name
+        let mut pos = 2;
+        while pos < upper_words.len()
+            && ["ONLY", "IF", "EXISTS"].contains(&upper_words[pos].as_str())
+        {
+            pos += 1;
+        }
+        pos += 1;
+
+        let action: Vec<&str> = upper_words.iter().skip(pos).map(String::as_str).collect();
+        match action.as_slice() {
+            ["ADD", "CONSTRAINT", _, _, ..] => self.constraints.push(NamedDefinition {
+                name: unquote_ident(words[pos + 2]),
+                definition: rest_of(statement, words[pos + 3]),
+            }),
+            ["ALTER", "COLUMN", _, "SET", "DEFAULT", _, ..]
+            | ["ALTER", _, "SET", "DEFAULT", _, ..] => {
+                let column_pos = if action[1] == "COLUMN" {
+                    pos + 2
+                } else {
+                    pos + 1
+                };
+                let column_name = unquote_ident(words[column_pos]);
+                let default = rest_of(statement, words[column_pos + 3]);
+                if let Some(column) = self.columns.iter_mut().find(|c| c.name == column_name) {
+                    column.default = Some(default);
+                }
+            }
+            ["ALTER", "COLUMN", _, "ADD", "GENERATED", ..] => {
+                let column_name = unquote_ident(words[pos + 2]);
+                let identity_end = upper_words
+                    .iter()
+                    .position(|w| w == "IDENTITY")
+                    .unwrap_or(upper_words.len() - 1);
+                let identity = words[pos + 4..=identity_end].join(" ");
+                if let Some(column) = self.columns.iter_mut().find(|c| c.name == column_name) {
+                    column.default = Some(identity);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl DataTypeDoc {
+    // Parse an enum, domain or composite type from the definition pulled by the DataTypePuller
+    pub fn from_definition(schema: &str, name: &str, definition: &str) -> Result<Option<Self>> {
+        let mut kind = None;
+        let mut description = None;
+
+        for statement in split_statements(definition) {
+            let words = split_words(&statement);
+            let upper_words: Vec<String> = words.iter().map(|w| w.to_uppercase()).collect();
+            let upper_words: Vec<&str> = upper_words.iter().map(String::as_str).collect();
+
+            match upper_words.as_slice() {
+                ["CREATE", "TYPE", _, "AS", "ENUM", ..] => {
+                    let labels_start = statement
+                        .find('(')
+                        .context(format!("The enum {} has no list of labels", name))?;
+                    let labels_end = find_closing_bracket(&statement, labels_start)
+                        .context(format!("The labels of the enum {} are never closed", name))?;
+
+                    kind = Some(DataTypeKind::Enum {
+                        labels: split_top_level(&statement[labels_start + 1..labels_end], ',')
+                            .into_iter()
+                            .filter_map(parse_string_literal)
+                            .collect(),
+                    });
+                }
+                ["CREATE", "TYPE", _, "AS", ..] => {
+                    let body_start = statement
+                        .find('(')
+                        .context(format!("The composite type {} has no attributes", name))?;
+                    let body_end =
+                        find_closing_bracket(&statement, body_start).context(format!(
+                            "The attributes of the composite type {} are never closed",
+                            name
+                        ))?;
+
+                    let attributes = split_top_level(&statement[body_start + 1..body_end], ',')
+                        .into_iter()
+                        .filter_map(|attribute| {
+                            let words = split_words(attribute.trim());
+                            let (attribute_name, attribute_type) = words.split_first()?;
+                            Some(ColumnDoc {
+                                name: unquote_ident(attribute_name),
+                                column_type: attribute_type.join(" "),
+                                nullable: true,
+                                ..Default::default()
+                            })
+                        })
+                        .collect();
+
+                    kind = Some(DataTypeKind::Composite { attributes });
+                }
+                ["CREATE", "DOMAIN", _, "AS", ..] => {
+                    let type_end = words
+                        .iter()
+                        .skip(4)
+                        .position(|word| {
+                            COLUMN_OPTION_KEYWORDS.contains(&word.to_uppercase().as_str())
+                        })
+                        .map(|pos| pos + 4)
+                        .unwrap_or(words.len());
+
+                    let (nullable, default, constraints) = parse_column_options(&words[type_end..]);
+                    kind = Some(DataTypeKind::Domain {
+                        base_type: words[4..type_end].join(" "),
+                        nullable,
+                        default,
+                        constraints,
+                    });
+                }
+                ["COMMENT", "ON", ..] => {
+                    if let Some(comment) = parse_comment(&statement) {
+                        description = comment.text;
+                    }
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(kind.map(|kind| Self {
+            schema: schema.to_string(),
+            name: name.to_string(),
+            kind,
+            description,
+        }))
+    }
+}
+
+// The parts of a COMMENT ON statement
+#[derive(Debug, PartialEq, Eq)]
+pub struct Comment {
+    pub object_type: String, // Upper case, eg. TABLE or MATERIALIZED VIEW
+    pub target: Vec<String>, // The unquoted parts of the name, eg. [schema, table, column]
+    pub text: Option<String>,
+}
+
+pub fn parse_comment(statement: &str) -> Option<Comment> {
+    let words = split_words(statement);
+    let is_pos = words.iter().rposition(|w| w.eq_ignore_ascii_case("IS"))?;
+    if is_pos < 4 {
+        return None;
+    }
+
+    Some(Comment {
+        object_type: words[2..is_pos - 1].join(" ").to_uppercase(),
+        target: split_top_level(words[is_pos - 1], '.')
+            .into_iter()
+            .map(unquote_ident)
+            .collect(),
+        text: parse_string_literal(&words[is_pos + 1..].join(" ")),
+    })
+}
+
+// Parse the value of a SQL string literal, returning None for NULL
+pub fn parse_string_literal(literal: &str) -> Option<String> {
+    let literal = literal.trim();
+
+    if let Some(escaped) = literal
+        .strip_prefix("E'")
+        .or_else(|| literal.strip_prefix("e'"))
+        .and_then(|l| l.strip_suffix('\''))
+    {
+        return Some(
+            escaped
+                .replace("\\n", "\n")
+                .replace("\\t", "\t")
+                .replace("\\'", "'")
+                .replace("''", "'")
+                .replace("\\\\", "\\"),
+        );
+    }
+
+    literal
+        .strip_prefix('\'')
+        .and_then(|l| l.strip_suffix('\''))
+        .map(|l| l.replace("''", "'"))
+}
+
+// Split SQL into its statements, removing any comments. Quoted text, including dollar quoted
+// function bodies, is kept intact
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut statements = vec![];
+    let mut current = String::new();
+
+    let mut idx = 0;
+    while idx < chars.len() {
+        let c = chars[idx];
+        let next = chars.get(idx + 1).copied();
+
+        match (c, next) {
+            ('-', Some('-')) => {
+                while idx < chars.len() && chars[idx] != '\n' {
+                    idx += 1;
+                }
+                continue;
+            }
+            ('/', Some('*')) => {
+                idx += 2;
+                while idx < chars.len() && !(chars[idx] == '*' && chars.get(idx + 1) == Some(&'/'))
+                {
+                    idx += 1;
+                }
+                idx += 2;
+                current.push(' ');
+                continue;
+            }
+            ('\'' | '"', _) => {
+                current.push(c);
+                idx += 1;
+                while idx < chars.len() {
+                    current.push(chars[idx]);
+                    idx += 1;
+                    if chars[idx - 1] == c {
+                        break;
+                    }
+                }
+                continue;
+            }
+            ('$', _) => {
+                if let Some(tag) = get_dollar_quote_tag(&chars, idx) {
+                    let tag_chars: Vec<char> = tag.chars().collect();
+                    current.push_str(&tag);
+                    idx += tag_chars.len();
+                    while idx < chars.len() {
+                        if chars[idx..].starts_with(&tag_chars) {
+                            current.push_str(&tag);
+                            idx += tag_chars.len();
+                            break;
+                        }
+                        current.push(chars[idx]);
+                        idx += 1;
+                    }
+                    continue;
+                }
+                current.push(c);
+            }
+            (';', _) => {
+                if !current.trim().is_empty() {
+                    statements.push(current.trim().to_string());
+                }
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+        idx += 1;
+    }
+
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+
+    statements
+}
+
+// Get the tag (eg. $function$ or $$) if a dollar quote starts at the position
+fn get_dollar_quote_tag(chars: &[char], start: usize) -> Option<String> {
+    if start > 0 && (chars[start - 1].is_alphanumeric() || chars[start - 1] == '_') {
+        return None;
+    }
+
+    let mut end = start + 1;
+    while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+        end += 1;
+    }
+
+    if end >= chars.len()
+        || chars[end] != '$'
+        || chars.get(start + 1).is_some_and(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    Some(chars[start..=end].iter().collect())
+}
+
+// Get the text from the start of the word to the end of the statement. The word must be a slice of
+// the statement
+fn rest_of(statement: &str, word: &str) -> String {
+    let start = word.as_ptr() as usize - statement.as_ptr() as usize;
+    statement[start..].trim().to_string()
+}
+
+// Parse the NOT NULL, DEFAULT and CONSTRAINT options that follow the type of a column or domain
+fn parse_column_options(words: &[&str]) -> (bool, Option<String>, Vec<NamedDefinition>) {
+    let mut nullable = true;
+    let mut default = None;
+    let mut constraints = vec![];
+
+    let is_keyword = |word: &str, exclude: &str| {
+        let upper = word.to_uppercase();
+        upper != exclude && upper != "NULL" && COLUMN_OPTION_KEYWORDS.contains(&upper.as_str())
+    };
+
+    let mut idx = 0;
+    while idx < words.len() {
+        let word = words[idx].to_uppercase();
+        match word.as_str() {
+            "NOT"
+                if words
+                    .get(idx + 1)
+                    .is_some_and(|w| w.eq_ignore_ascii_case("NULL")) =>
+            {
+                nullable = false;
+                idx += 2;
+            }
+            "PRIMARY" => {
+                nullable = false;
+                idx += 2;
+            }
+            "DEFAULT" | "GENERATED" => {
+                let end = words
+                    .iter()
+                    .skip(idx + 1)
+                    .position(|w| is_keyword(w, ""))
+                    .map(|pos| pos + idx + 1)
+                    .unwrap_or(words.len());
+                let value = words[idx + 1..end].join(" ");
+                default = Some(if word == "GENERATED" {
+                    format!("GENERATED {}", value)
+                } else {
+                    value
+                });
+                idx = end;
+            }
+            "CONSTRAINT" if idx + 2 < words.len() => {
+                let end = words
+                    .iter()
+                    .skip(idx + 2)
+                    .position(|w| w.eq_ignore_ascii_case("CONSTRAINT"))
+                    .map(|pos| pos + idx + 2)
+                    .unwrap_or(words.len());
+                let definition = words[idx + 2..end].join(" ");
+
+                if definition.eq_ignore_ascii_case("NOT NULL") {
+                    nullable = false;
+                } else {
+                    constraints.push(NamedDefinition {
+                        name: unquote_ident(words[idx + 1]),
+                        definition,
+                    });
+                }
+                idx = end;
+            }
+            "CHECK" | "REFERENCES" | "UNIQUE" => {
+                let end = words
+                    .iter()
+                    .skip(idx + 1)
+                    .position(|w| is_keyword(w, "CHECK"))
+                    .map(|pos| pos + idx + 1)
+                    .unwrap_or(words.len());
+                constraints.push(NamedDefinition {
+                    name: String::new(),
+                    definition: words[idx..end].join(" "),
+                });
+                idx = end;
+            }
+            _ => idx += 1,
+        }
+    }
+
+    (nullable, default, constraints)
+}
+
+// Get the names of the output columns of a SELECT query
+fn get_select_output_names(query: &str) -> Vec<String> {
+    let words = split_words(query);
+    if !words
+        .first()
+        .is_some_and(|w| w.eq_ignore_ascii_case("SELECT"))
+    {
+        return vec![];
+    }
+
+    let mut select_start = 1;
+    if words
+        .get(1)
+        .is_some_and(|w| w.eq_ignore_ascii_case("DISTINCT"))
+    {
+        select_start = 2;
+        if words.get(2).is_some_and(|w| w.eq_ignore_ascii_case("ON")) {
+            select_start = 4;
+        }
+    }
+
+    let select_list = match (
+        words.get(select_start),
+        words.iter().position(|w| w.eq_ignore_ascii_case("FROM")),
+    ) {
+        (Some(first_word), Some(from_pos)) if from_pos >= select_start => {
+            let start = first_word.as_ptr() as usize - query.as_ptr() as usize;
+            let end = words[from_pos].as_ptr() as usize - query.as_ptr() as usize;
+            &query[start..end]
+        }
+        (Some(first_word), _) => &query[first_word.as_ptr() as usize - query.as_ptr() as usize..],
+        _ => return vec![],
+    };
+
+    split_top_level(select_list, ',')
+        .into_iter()
+        .filter_map(|item| {
+            let item_words = split_words(item.trim());
+            let last_word = item_words.last()?;
+            if item_words.len() >= 3 && item_words[item_words.len() - 2].eq_ignore_ascii_case("AS")
+            {
+                return Some(unquote_ident(last_word));
+            }
+            split_top_level(last_word, '.')
+                .last()
+                .map(|name| unquote_ident(name))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_statements_works() {
+        let sql = r#"
+-- A comment; with a semicolon
+SET search_path = '';
+CREATE FUNCTION public.f() RETURNS text AS $function$ SELECT 'a;b'; $function$ LANGUAGE sql;
+/* Block; comment */ COMMENT ON TABLE public.t IS 'It''s; here';
+"#;
+
+        assert_eq!(
+            split_statements(sql),
+            vec![
+                "SET search_path = ''",
+                "CREATE FUNCTION public.f() RETURNS text AS $function$ SELECT 'a;b'; $function$ LANGUAGE sql",
+                "COMMENT ON TABLE public.t IS 'It''s; here'",
+            ]
+        );
+    }
+
+    #[test]
+    fn table_from_pg_dump_works() {
+        let ddl = r#"
+--
+-- PostgreSQL database dump
+--
+
+SET statement_timeout = 0;
+
+CREATE TABLE public.people (
+    id integer NOT NULL,
+    name character varying(50) DEFAULT 'Homer Simpson'::character varying NOT NULL,
+    age integer,
+    CONSTRAINT people_age_check CHECK ((age > 0))
+);
+
+COMMENT ON TABLE public.people IS 'Everyone we know';
+
+COMMENT ON COLUMN public.people.name IS 'Their full name';
+
+ALTER TABLE public.people ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+    SEQUENCE NAME public.people_id_seq
+    START WITH 1
+);
+
+ALTER TABLE ONLY public.people
+    ADD CONSTRAINT people_pkey PRIMARY KEY (id);
+
+CREATE INDEX people_name_idx ON public.people USING btree (name);
+"#;
+
+        let table = RelationDoc::from_pg_dump("public", "people", ddl)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(table.kind, RelationKind::Table);
+        assert_eq!(table.description.as_deref(), Some("Everyone we know"));
+        assert_eq!(
+            table.columns,
+            vec![
+                ColumnDoc {
+                    name: "id".to_string(),
+                    column_type: "integer".to_string(),
+                    nullable: false,
+                    default: Some("GENERATED ALWAYS AS IDENTITY".to_string()),
+                    description: None,
+                },
+                ColumnDoc {
+                    name: "name".to_string(),
+                    column_type: "character varying(50)".to_string(),
+                    nullable: false,
+                    default: Some("'Homer Simpson'::character varying".to_string()),
+                    description: Some("Their full name".to_string()),
+                },
+                ColumnDoc {
+                    name: "age".to_string(),
+                    column_type: "integer".to_string(),
+                    nullable: true,
+                    default: None,
+                    description: None,
+                },
+            ]
+        );
+        assert_eq!(
+            table.constraints,
+            vec![
+                NamedDefinition {
+                    name: "people_age_check".to_string(),
+                    definition: "CHECK ((age > 0))".to_string(),
+                },
+                NamedDefinition {
+                    name: "people_pkey".to_string(),
+                    definition: "PRIMARY KEY (id)".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            table.indexes,
+            vec![NamedDefinition {
+                name: "people_name_idx".to_string(),
+                definition: "CREATE INDEX people_name_idx ON public.people USING btree (name);"
+                    .to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn view_from_pg_dump_works() {
+        let ddl = r#"
+CREATE VIEW public.adults AS
+ SELECT people.name,
+    (people.age + 1) AS next_age
+   FROM public.people
+  WHERE (people.age >= 18);
+
+COMMENT ON VIEW public.adults IS 'People old enough to vote';
+COMMENT ON COLUMN public.adults.next_age IS 'Their age at their next birthday';
+"#;
+
+        let view = RelationDoc::from_pg_dump("public", "adults", ddl)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(view.kind, RelationKind::View);
+        assert_eq!(
+            view.description.as_deref(),
+            Some("People old enough to vote")
+        );
+        assert_eq!(
+            view.columns
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["name", "next_age"]
+        );
+        assert_eq!(
+            view.columns[1].description.as_deref(),
+            Some("Their age at their next birthday")
+        );
+        assert!(view.query.unwrap().starts_with("SELECT people.name,"));
+    }
+
+    #[test]
+    fn data_type_from_definition_works() {
+        let enum_type = DataTypeDoc::from_definition(
+            "public",
+            "mood",
+            "CREATE TYPE public.mood AS ENUM ('happy', 'it''s complicated');\n\nCOMMENT ON TYPE public.mood IS 'How someone feels';",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            enum_type.kind,
+            DataTypeKind::Enum {
+                labels: vec!["happy".to_string(), "it's complicated".to_string()]
+            }
+        );
+        assert_eq!(enum_type.description.as_deref(), Some("How someone feels"));
+
+        let domain = DataTypeDoc::from_definition(
+            "public",
+            "positive_int",
+            "CREATE DOMAIN public.positive_int AS integer NOT NULL\n DEFAULT 1\nCONSTRAINT positive_int_check CHECK (VALUE > 0);",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            domain.kind,
+            DataTypeKind::Domain {
+                base_type: "integer".to_string(),
+                nullable: false,
+                default: Some("1".to_string()),
+                constraints: vec![NamedDefinition {
+                    name: "positive_int_check".to_string(),
+                    definition: "CHECK (VALUE > 0)".to_string(),
+                }],
+            }
+        );
+
+        let composite = DataTypeDoc::from_definition(
+            "public",
+            "address",
+            "CREATE TYPE public.address AS (\n\tstreet text,\n\tpost_code character varying(10)\n);",
+        )
+        .unwrap()
+        .unwrap();
+        match composite.kind {
+            DataTypeKind::Composite { attributes } => {
+                assert_eq!(attributes[1].name, "post_code");
+                assert_eq!(attributes[1].column_type, "character varying(10)");
+            }
+            _ => panic!("The type should be parsed as a composite type"),
+        }
+    }
+}
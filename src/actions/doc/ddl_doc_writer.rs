@@ -0,0 +1,335 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+use super::{
+    ddl_doc_parser::{
+        ColumnDoc, DataTypeDoc, DataTypeKind, NamedDefinition, RelationDoc, RelationKind,
+    },
+    doc_writer::MARKDOWN_DOC_ROOT,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ObjectKind {
+    Table,
+    View,
+    MaterializedView,
+    Enum,
+    Domain,
+    CompositeType,
+}
+
+impl ObjectKind {
+    // The directory the docs are written to within the schemas documentation
+    pub fn dir(&self) -> &'static str {
+        match self {
+            Self::Table => "tables",
+            Self::View | Self::MaterializedView => "views",
+            Self::Enum | Self::Domain | Self::CompositeType => "data_types",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Table => "Table",
+            Self::View => "View",
+            Self::MaterializedView => "Materialized View",
+            Self::Enum => "Enum",
+            Self::Domain => "Domain",
+            Self::CompositeType => "Composite Type",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocSection {
+    Table {
+        heading: &'static str,
+        headers: Vec<&'static str>,
+        rows: Vec<Vec<String>>,
+    },
+    Code {
+        heading: &'static str,
+        code: String,
+    },
+}
+
+// The documentation of a table, view or data type in a form that can be written in any format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectDoc {
+    pub schema: String,
+    pub name: String,
+    pub kind: ObjectKind,
+    pub description: Option<String>,
+    pub sections: Vec<DocSection>,
+}
+
+fn column_rows(columns: &[ColumnDoc], include_types: bool) -> Vec<Vec<String>> {
+    columns
+        .iter()
+        .map(|column| {
+            let description = column.description.clone().unwrap_or_default();
+            if !include_types {
+                return vec![column.name.clone(), description];
+            }
+
+            vec![
+                column.name.clone(),
+                column.column_type.clone(),
+                if column.nullable { "YES" } else { "NO" }.to_string(),
+                column.default.clone().unwrap_or_default(),
+                description,
+            ]
+        })
+        .collect()
+}
+
+fn named_definition_section(
+    heading: &'static str,
+    definitions: &[NamedDefinition],
+) -> Option<DocSection> {
+    if definitions.is_empty() {
+        return None;
+    }
+
+    Some(DocSection::Table {
+        heading,
+        headers: vec!["Name", "Definition"],
+        rows: definitions
+            .iter()
+            .map(|d| vec![d.name.clone(), d.definition.clone()])
+            .collect(),
+    })
+}
+
+impl From<&RelationDoc> for ObjectDoc {
+    fn from(relation: &RelationDoc) -> Self {
+        let kind = match relation.kind {
+            RelationKind::Table => ObjectKind::Table,
+            RelationKind::View => ObjectKind::View,
+            RelationKind::MaterializedView => ObjectKind::MaterializedView,
+        };
+
+        let mut sections = vec![];
+        if !relation.columns.is_empty() {
+            sections.push(match kind {
+                ObjectKind::Table => DocSection::Table {
+                    heading: "Columns",
+                    headers: vec!["Name", "Type", "Nullable", "Default", "Description"],
+                    rows: column_rows(&relation.columns, true),
+                },
+                _ => DocSection::Table {
+                    heading: "Columns",
+                    headers: vec!["Name", "Description"],
+                    rows: column_rows(&relation.columns, false),
+                },
+            });
+        }
+        sections.extend(named_definition_section(
+            "Constraints",
+            &relation.constraints,
+        ));
+        sections.extend(named_definition_section("Indexes", &relation.indexes));
+        if let Some(query) = &relation.query {
+            sections.push(DocSection::Code {
+                heading: "Query",
+                code: query.clone(),
+            });
+        }
+
+        Self {
+            schema: relation.schema.clone(),
+            name: relation.name.clone(),
+            kind,
+            description: relation.description.clone(),
+            sections,
+        }
+    }
+}
+
+impl From<&DataTypeDoc> for ObjectDoc {
+    fn from(data_type: &DataTypeDoc) -> Self {
+        let (kind, sections) = match &data_type.kind {
+            DataTypeKind::Enum { labels } => (
+                ObjectKind::Enum,
+                vec![DocSection::Table {
+                    heading: "Values",
+                    headers: vec!["Value"],
+                    rows: labels.iter().map(|label| vec![label.clone()]).collect(),
+                }],
+            ),
+            DataTypeKind::Domain {
+                base_type,
+                nullable,
+                default,
+                constraints,
+            } => {
+                let mut sections = vec![DocSection::Table {
+                    heading: "Definition",
+                    headers: vec!["Base Type", "Nullable", "Default"],
+                    rows: vec![vec![
+                        base_type.clone(),
+                        if *nullable { "YES" } else { "NO" }.to_string(),
+                        default.clone().unwrap_or_default(),
+                    ]],
+                }];
+                sections.extend(named_definition_section("Constraints", constraints));
+                (ObjectKind::Domain, sections)
+            }
+            DataTypeKind::Composite { attributes } => (
+                ObjectKind::CompositeType,
+                vec![DocSection::Table {
+                    heading: "Attributes",
+                    headers: vec!["Name", "Type"],
+                    rows: attributes
+                        .iter()
+                        .map(|a| vec![a.name.clone(), a.column_type.clone()])
+                        .collect(),
+                }],
+            ),
+        };
+
+        Self {
+            schema: data_type.schema.clone(),
+            name: data_type.name.clone(),
+            kind,
+            description: data_type.description.clone(),
+            sections,
+        }
+    }
+}
+
+// Writes the docs of tables, views and data types as markdown
+pub struct DdlDocWriter {}
+
+impl DdlDocWriter {
+    fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+        let clean = |cell: &str| {
+            cell.replace("\r\n", " ")
+                .replace('\n', " ")
+                .replace('|', "\\|")
+        };
+
+        // Get the text width required
+        let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+        for row in rows {
+            for (idx, cell) in row.iter().enumerate() {
+                widths[idx] = widths[idx].max(clean(cell).chars().count());
+            }
+        }
+
+        let format_row = |cells: Vec<String>| -> String {
+            let cells: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect();
+            format!("| {} |\n", cells.join(" | "))
+        };
+
+        let mut table = format_row(headers.iter().map(|h| h.to_string()).collect());
+        table.push_str(&format_row(widths.iter().map(|w| "-".repeat(*w)).collect()));
+        for row in rows {
+            table.push_str(&format_row(row.iter().map(|cell| clean(cell)).collect()));
+        }
+
+        table
+    }
+
+    pub fn to_markdown(object: &ObjectDoc) -> String {
+        let mut file_content = format!("# {}\n\n", object.name);
+        file_content.push_str(&format!(
+            "- {}: {}.{}\n\n",
+            object.kind.label(),
+            object.schema,
+            object.name
+        ));
+
+        if let Some(description) = &object.description {
+            file_content.push_str(&format!("### Description\n{}\n\n", description));
+        }
+
+        for section in &object.sections {
+            match section {
+                DocSection::Table {
+                    heading,
+                    headers,
+                    rows,
+                } => {
+                    file_content.push_str(&format!("### {}\n\n", heading));
+                    file_content.push_str(&Self::markdown_table(headers, rows));
+                    file_content.push('\n');
+                }
+                DocSection::Code { heading, code } => {
+                    file_content.push_str(&format!("### {}\n\n```sql\n{}\n```\n\n", heading, code));
+                }
+            }
+        }
+
+        file_content
+    }
+
+    pub async fn write_doc_to_file(object: &ObjectDoc) -> Result<()> {
+        let file_name = format!(
+            "{}/{}/{}/{}.md",
+            MARKDOWN_DOC_ROOT,
+            object.schema,
+            object.kind.dir(),
+            object.name
+        );
+        let file_path = std::path::Path::new(&file_name);
+
+        tokio::fs::create_dir_all(
+            file_path
+                .parent()
+                .context("This file should have a parent directory")?,
+        )
+        .await?;
+        tokio::fs::write(file_path, Self::to_markdown(object)).await?;
+
+        println!("\t{} Docs Generated", object.name.bold().magenta());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_markdown_works() {
+        let relation = RelationDoc {
+            schema: "public".to_string(),
+            name: "people".to_string(),
+            kind: RelationKind::Table,
+            description: Some("Everyone we know".to_string()),
+            columns: vec![ColumnDoc {
+                name: "name".to_string(),
+                column_type: "text".to_string(),
+                nullable: false,
+                default: None,
+                description: Some("Their | name".to_string()),
+            }],
+            constraints: vec![],
+            indexes: vec![],
+            query: None,
+        };
+
+        assert_eq!(
+            DdlDocWriter::to_markdown(&ObjectDoc::from(&relation)),
+            r#"# people
+
+- Table: public.people
+
+### Description
+Everyone we know
+
+### Columns
+
+| Name | Type | Nullable | Default | Description   |
+| ---- | ---- | -------- | ------- | ------------- |
+| name | text | NO       |         | Their \| name |
+
+"#
+        );
+    }
+}
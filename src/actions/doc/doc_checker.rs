@@ -0,0 +1,282 @@
+use crate::actions::function_signature::{unquote_ident, ArgMode, FunctionSignature};
+
+use super::doc_parser::FunctionDocParser;
+
+// Alternative names for the same type, mapped to the name used by format_type
+const TYPE_ALIASES: [(&str, &str); 17] = [
+    ("int", "integer"),
+    ("int4", "integer"),
+    ("int2", "smallint"),
+    ("int8", "bigint"),
+    ("float", "double precision"),
+    ("float8", "double precision"),
+    ("float4", "real"),
+    ("bool", "boolean"),
+    ("varchar", "character varying"),
+    ("char", "character"),
+    ("bpchar", "character"),
+    ("decimal", "numeric"),
+    ("timestamp", "timestamp without time zone"),
+    ("timestamptz", "timestamp with time zone"),
+    ("time", "time without time zone"),
+    ("timetz", "time with time zone"),
+    ("varbit", "bit varying"),
+];
+
+// Checks that the doc comments of a function agree with its actual signature
+pub struct DocChecker {}
+
+impl DocChecker {
+    // Normalise a type so that aliases, type modifiers, casing and the pg_catalog schema don't
+    // cause false mismatches
+    pub fn normalise_type(type_name: &str) -> String {
+        let mut normalised = type_name
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .to_lowercase();
+
+        let mut array_suffix = String::new();
+        while let Some(stripped) = normalised.strip_suffix("[]") {
+            array_suffix.push_str("[]");
+            normalised = stripped.trim_end().to_string();
+        }
+
+        // Only the modifiers are removed as the name can continue after them, such as in
+        // timestamp(3) with time zone
+        if let Some(modifier_start) = normalised.find('(') {
+            let modifier_end = normalised[modifier_start..]
+                .find(')')
+                .map(|end| modifier_start + end + 1)
+                .unwrap_or(normalised.len());
+            normalised = format!(
+                "{} {}",
+                normalised[..modifier_start].trim_end(),
+                normalised[modifier_end..].trim_start()
+            );
+        }
+        let normalised = normalised.trim();
+        let normalised = normalised.strip_prefix("pg_catalog.").unwrap_or(normalised);
+
+        let normalised = TYPE_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == normalised)
+            .map(|(_, name)| *name)
+            .unwrap_or(normalised);
+
+        format!("{}{}", normalised, array_suffix)
+    }
+
+    fn types_match(documented: &str, actual: &str) -> bool {
+        Self::normalise_type(documented) == Self::normalise_type(actual)
+    }
+
+    fn check_params(
+        function_info: &FunctionDocParser<'_>,
+        signature: &FunctionSignature,
+    ) -> Vec<String> {
+        let mut issues = vec![];
+        let params = function_info.params.as_deref().unwrap_or_default();
+        let input_args: Vec<_> = signature.input_args().collect();
+
+        let arg_names: Vec<&str> = signature
+            .args
+            .iter()
+            .filter_map(|arg| arg.name.as_deref())
+            .collect();
+
+        for (idx, param) in params.iter().enumerate() {
+            let param_name = unquote_ident(param.name);
+            let is_positional = input_args.get(idx).is_some_and(|arg| arg.name.is_none());
+
+            if !arg_names.contains(&param_name.as_str()) && !is_positional {
+                issues.push(format!(
+                    "@param {} is not an argument of the function",
+                    param.name
+                ));
+            }
+        }
+
+        for (idx, arg) in input_args.iter().enumerate() {
+            // Unnamed arguments can only be matched to the @param in the same position
+            let param = match &arg.name {
+                Some(name) => params
+                    .iter()
+                    .find(|param| unquote_ident(param.name) == *name),
+                None => params
+                    .get(idx)
+                    .filter(|param| !arg_names.contains(&unquote_ident(param.name).as_str())),
+            };
+            let arg_label = arg.name.clone().unwrap_or_else(|| format!("${}", idx + 1));
+
+            match param {
+                None => issues.push(format!("Argument {} has no @param", arg_label)),
+                Some(param) => {
+                    let arg_type = match arg.mode {
+                        // The elements of a variadic array can be documented instead of the array
+                        ArgMode::Variadic
+                            if Self::types_match(
+                                param.param_type,
+                                arg.arg_type.trim_end_matches("[]"),
+                            ) =>
+                        {
+                            continue
+                        }
+                        _ => &arg.arg_type,
+                    };
+
+                    if !Self::types_match(param.param_type, arg_type) {
+                        issues.push(format!(
+                            "@param {} is documented as {{{}}} but the argument is of type {}",
+                            param.name, param.param_type, arg_type
+                        ));
+                    }
+                }
+            }
+        }
+
+        issues
+    }
+
+    fn check_return(
+        function_info: &FunctionDocParser<'_>,
+        signature: &FunctionSignature,
+    ) -> Option<String> {
+        let documented = function_info.returns.as_ref().map(|r| r.return_type);
+        let returns_table = signature.args.iter().any(|arg| arg.mode == ArgMode::Table);
+
+        let actual = match (&signature.return_type, returns_table) {
+            (None, _) => {
+                return documented.map(|documented| {
+                    format!(
+                        "@return is documented as {{{}}} but procedures do not return a value",
+                        documented
+                    )
+                })
+            }
+            (Some(_), true) => String::from("TABLE"),
+            (Some(return_type), false) if signature.returns_set => format!("SETOF {}", return_type),
+            (Some(return_type), false) => return_type.clone(),
+        };
+
+        let documented = match documented {
+            Some(documented) => documented,
+            None if Self::types_match(&actual, "void") => return None,
+            None => {
+                return Some(format!(
+                    "The function returns {} but has no @return",
+                    actual
+                ))
+            }
+        };
+
+        let matches = match (returns_table, signature.returns_set) {
+            (true, _) => documented.trim().to_uppercase().starts_with("TABLE"),
+            (false, true) => {
+                documented
+                    .trim()
+                    .get(..6)
+                    .is_some_and(|setof| setof.eq_ignore_ascii_case("SETOF "))
+                    && Self::types_match(
+                        &documented.trim()[6..],
+                        signature.return_type.as_deref().unwrap_or_default(),
+                    )
+            }
+            (false, false) => Self::types_match(documented, &actual),
+        };
+
+        if matches {
+            return None;
+        }
+
+        Some(format!(
+            "@return is documented as {{{}}} but the function returns {}",
+            documented, actual
+        ))
+    }
+
+    // Get every way in which the doc comment disagrees with the signature of the function
+    pub fn check(
+        function_info: &FunctionDocParser<'_>,
+        signature: &FunctionSignature,
+    ) -> Vec<String> {
+        let mut issues = Self::check_params(function_info, signature);
+        issues.extend(Self::check_return(function_info, signature));
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalise_type_works() {
+        assert_eq!(DocChecker::normalise_type("INT4"), "integer");
+        assert_eq!(
+            DocChecker::normalise_type("VARCHAR(50)"),
+            "character varying"
+        );
+        assert_eq!(DocChecker::normalise_type("pg_catalog.int8 []"), "bigint[]");
+        assert_eq!(
+            DocChecker::normalise_type("timestamptz"),
+            DocChecker::normalise_type("timestamp with time zone")
+        );
+        assert_eq!(
+            DocChecker::normalise_type("timestamp(3) with time zone"),
+            DocChecker::normalise_type("timestamptz")
+        );
+        assert_eq!(
+            DocChecker::normalise_type("TIME (6) WITHOUT TIME ZONE[]"),
+            "time without time zone[]"
+        );
+    }
+
+    #[test]
+    fn check_works() {
+        let definition = r#"CREATE OR REPLACE FUNCTION public.concatenating(var1 text, var2 integer)
+ RETURNS SETOF text
+ LANGUAGE plpgsql
+AS $function$
+	BEGIN
+	/**
+		This is the function description
+
+		@param {TEXT} var1 The first part of the output
+		@param {TEXT} var3 This argument was renamed
+
+		@return {TEXT} var1 and var2 concatenated together
+	*/
+		return NEXT var1 || var2;
+	END
+$function$"#;
+
+        let signature = FunctionSignature::from_definition(
+            "public",
+            "concatenating(text, integer)",
+            definition,
+        )
+        .unwrap();
+        let function_info = FunctionDocParser::new("public", "concatenating", definition)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            DocChecker::check(&function_info, &signature),
+            vec![
+                "@param var3 is not an argument of the function",
+                "Argument var2 has no @param",
+                "@return is documented as {TEXT} but the function returns SETOF text",
+            ]
+        );
+
+        let definition = definition
+            .replace("{TEXT} var3", "{INT} var2")
+            .replace("@return {TEXT}", "@return {SETOF text}");
+        let function_info = FunctionDocParser::new("public", "concatenating", &definition)
+            .unwrap()
+            .unwrap();
+
+        assert!(DocChecker::check(&function_info, &signature).is_empty());
+    }
+}
@@ -1,7 +1,131 @@
+use std::{borrow::Cow, collections::HashMap};
+
 use anyhow::{Context, Result};
+use sqlx::{PgPool, Row};
+
+use crate::config_file_manager::user_config::DocCommentStyle;
+
+const FUNCTION_COMMENT_QUERY: &str = "
+    SELECT
+        format('%I(%s)', p.proname, oidvectortypes(p.proargtypes)) AS overload_name,
+        obj_description(p.oid, 'pg_proc') AS comment
+    FROM pg_proc p INNER JOIN pg_namespace ns ON (p.pronamespace = ns.oid)
+    WHERE ns.nspname = $1
+    AND p.prokind IN ('f', 'p')
+    AND obj_description(p.oid, 'pg_proc') IS NOT NULL
+";
+
+// Finds the doc comment of a function, removing the comment syntax so that only the description
+// and the tags are left
+pub trait DocCommentParser {
+    fn find_doc_comment<'f>(
+        &self,
+        overload_name: &str,
+        file_contents: &'f str,
+    ) -> Result<Option<Cow<'f, str>>>;
+}
+
+// A JS doc style /** */ block comment within the function
+pub struct BlockCommentParser {}
+
+impl DocCommentParser for BlockCommentParser {
+    fn find_doc_comment<'f>(
+        &self,
+        _overload_name: &str,
+        file_contents: &'f str,
+    ) -> Result<Option<Cow<'f, str>>> {
+        Ok(FunctionDocParser::find_doc_comment(file_contents)?.map(Cow::Borrowed))
+    }
+}
+
+// The first run of consecutive -- line comments within the function that contains a tag
+pub struct LineCommentParser {}
+
+impl DocCommentParser for LineCommentParser {
+    fn find_doc_comment<'f>(
+        &self,
+        _overload_name: &str,
+        file_contents: &'f str,
+    ) -> Result<Option<Cow<'f, str>>> {
+        let mut comment_lines: Vec<&str> = vec![];
+        for line in file_contents.lines().map(str::trim) {
+            if let Some(comment) = line.strip_prefix("--") {
+                comment_lines.push(comment.trim_start_matches('-').trim());
+                continue;
+            }
+
+            if comment_lines
+                .iter()
+                .any(|comment| FunctionDocParser::find_next_tag(comment).is_some())
+            {
+                break;
+            }
+            comment_lines.clear();
+        }
+
+        if !comment_lines
+            .iter()
+            .any(|comment| FunctionDocParser::find_next_tag(comment).is_some())
+        {
+            return Ok(None);
+        }
 
-// This could easily be made a trait in the future so that different doc comment styles can be
-// adopted. Only supporting JS doc notation for now
+        Ok(Some(Cow::Owned(comment_lines.join("\n").trim().to_string())))
+    }
+}
+
+// The COMMENT ON FUNCTION text stored on the database, keyed by overload name
+pub struct CommentOnParser {
+    comments: HashMap<String, String>,
+}
+
+impl CommentOnParser {
+    pub async fn from_catalog(pool: &PgPool, schema: &str) -> Result<Self> {
+        let rows = sqlx::query(FUNCTION_COMMENT_QUERY)
+            .bind(schema)
+            .fetch_all(pool)
+            .await?;
+
+        let mut comments = HashMap::new();
+        for row in rows {
+            comments.insert(row.try_get("overload_name")?, row.try_get("comment")?);
+        }
+
+        Ok(Self { comments })
+    }
+}
+
+impl DocCommentParser for CommentOnParser {
+    fn find_doc_comment<'f>(
+        &self,
+        overload_name: &str,
+        _file_contents: &'f str,
+    ) -> Result<Option<Cow<'f, str>>> {
+        Ok(self
+            .comments
+            .get(overload_name)
+            .map(|comment| Cow::Owned(comment.trim().to_string())))
+    }
+}
+
+// Get the parser for the doc comment style, reading the comments from the database if they are
+// stored there
+pub async fn get_doc_comment_parser(
+    style: DocCommentStyle,
+    schema: &str,
+) -> Result<Box<dyn DocCommentParser>> {
+    Ok(match style {
+        DocCommentStyle::Block => Box::new(BlockCommentParser {}),
+        DocCommentStyle::Line => Box::new(LineCommentParser {}),
+        DocCommentStyle::CommentOn => {
+            let connection = crate::db_manager::DbConnection::new().await?;
+            Box::new(CommentOnParser::from_catalog(connection.get_connection_pool(), schema).await?)
+        }
+    })
+}
+
+// The description and tags parsed from the doc comment of a function, whichever style it was
+// written in
 #[derive(Clone, Default, Debug, Eq, PartialEq)]
 pub struct FunctionDocParser<'f> {
     pub function_name: &'f str,      // Get this from the file path
@@ -10,9 +134,13 @@ pub struct FunctionDocParser<'f> {
     pub description: &'f str,
     pub author: Option<&'f str>,                // @author Homer Simpson
     pub date: Option<&'f str>,                  // @date 01/02/1234
-    pub example: Option<&'f str>,               // @example SELECT * FROM foobarbaz
+    pub since: Option<&'f str>,                 // @since 1.2.0
+    pub deprecated: Option<&'f str>,            // @deprecated Use other_func instead
+    pub examples: Vec<&'f str>,                 // @example SELECT * FROM foobarbaz
     pub params: Option<Vec<FunctionParam<'f>>>, // @param {TEXT} the_name the description
     pub returns: Option<FunctionReturn<'f>>,    // @return {TEXT} the description
+    pub throws: Vec<FunctionThrows<'f>>,        // @throws {P0001} the description
+    pub see: Vec<FunctionSee<'f>>,              // @see other_schema.other_func the description
 }
 
 impl<'f> FunctionDocParser<'f> {
@@ -27,17 +155,90 @@ impl<'f> FunctionDocParser<'f> {
             None => return Ok(None)
         };
 
-        return Ok(Some(Self {
+        Self::from_doc_comment(schema, function_name, file_contents, doc_comment).map(Some)
+    }
+
+    // Parse a doc comment that has already had its comment syntax removed by a DocCommentParser
+    pub fn from_doc_comment(
+        schema: &'f str,
+        function_name: &'f str,
+        file_contents: &'f str,
+        doc_comment: &'f str,
+    ) -> Result<Self> {
+        Ok(Self {
             function_name,
             function_full_name: Self::get_full_name(function_name, file_contents)?,
             schema,
             description: Self::get_description(doc_comment)?,
             author: Self::get_single_doc_tag(doc_comment, "@author"),
             date: Self::get_single_doc_tag(doc_comment, "@date"),
-            example: Self::get_single_doc_tag(doc_comment, "@example"),
+            since: Self::get_single_doc_tag(doc_comment, "@since"),
+            deprecated: Self::get_single_doc_tag(doc_comment, "@deprecated"),
+            examples: Self::get_all_doc_tags(doc_comment, "@example"),
             params: Self::get_params(doc_comment)?,
             returns: Self::get_return(doc_comment)?,
-        }));
+            throws: Self::get_all_doc_tags(doc_comment, "@throws")
+                .into_iter()
+                .map(FunctionThrows::new)
+                .collect::<Result<Vec<FunctionThrows>>>()?,
+            see: Self::get_all_doc_tags(doc_comment, "@see")
+                .into_iter()
+                .map(FunctionSee::new)
+                .collect(),
+        })
+    }
+
+    // Render the docs as the text of a COMMENT ON statement. The tags are kept so that the comment
+    // can itself be parsed by the comment_on doc comment style
+    pub fn to_comment_text(&self) -> String {
+        let mut tags = vec![];
+        let with_description = |tag: String, description: Option<&str>| match description {
+            Some(description) => format!("{} {}", tag, description),
+            None => tag,
+        };
+
+        if let Some(author) = self.author {
+            tags.push(format!("@author {}", author));
+        }
+        if let Some(date) = self.date {
+            tags.push(format!("@date {}", date));
+        }
+        if let Some(since) = self.since {
+            tags.push(format!("@since {}", since));
+        }
+        if let Some(reason) = self.deprecated {
+            tags.push(format!("@deprecated {}", reason).trim_end().to_string());
+        }
+        for param in self.params.iter().flatten() {
+            tags.push(with_description(
+                format!("@param {{{}}} {}", param.param_type, param.name),
+                param.description,
+            ));
+        }
+        if let Some(returns) = &self.returns {
+            tags.push(with_description(
+                format!("@return {{{}}}", returns.return_type),
+                returns.description,
+            ));
+        }
+        for throws in &self.throws {
+            tags.push(with_description(
+                format!("@throws {{{}}}", throws.sqlstate),
+                throws.description,
+            ));
+        }
+        for example in &self.examples {
+            tags.push(format!("@example {}", example));
+        }
+        for see in &self.see {
+            tags.push(with_description(format!("@see {}", see.target), see.description));
+        }
+
+        match (self.description.is_empty(), tags.is_empty()) {
+            (_, true) => self.description.to_string(),
+            (true, false) => tags.join("\n"),
+            (false, false) => format!("{}\n\n{}", self.description, tags.join("\n")),
+        }
     }
 
     pub fn find_doc_comment(file_contents: &'f str) -> Result<Option<&'f str>> {
@@ -69,25 +270,76 @@ impl<'f> FunctionDocParser<'f> {
     }
 
     pub fn get_description(doc_comment: &'f str) -> Result<&'f str> {
-        let description_end = doc_comment.find('@').unwrap_or(doc_comment.len());
+        let description_end = Self::find_next_tag(doc_comment).unwrap_or(doc_comment.len());
 
-        return Ok(doc_comment[..description_end].trim());
+        Ok(doc_comment[..description_end].trim())
     }
 
-    pub fn get_single_doc_tag(doc_comment: &'f str, element_name: &str) -> Option<&'f str> {
-        let start_element = doc_comment.find(element_name);
-        start_element?;
-        let start_element = start_element.unwrap() + element_name.len();
+    // Tags have to start a word, so an email address within the author isn't mistaken for a tag
+    fn starts_word(text: &str, idx: usize) -> bool {
+        text[..idx]
+            .chars()
+            .next_back()
+            .is_none_or(char::is_whitespace)
+    }
+
+    // Find the start of the next tag, ignoring any @ which isn't followed by a letter (such as the
+    // @> operator within an example)
+    pub fn find_next_tag(text: &str) -> Option<usize> {
+        text.match_indices('@')
+            .find(|(idx, _)| {
+                Self::starts_word(text, *idx)
+                    && text[idx + 1..]
+                        .chars()
+                        .next()
+                        .is_some_and(|c| c.is_ascii_alphabetic())
+            })
+            .map(|(idx, _)| idx)
+    }
 
-        let end_element = match doc_comment[start_element..].find('@') {
+    // Find every occurrence of the tag, skipping any longer tag that starts with the same name
+    fn find_doc_tags(doc_comment: &str, element_name: &str) -> Vec<usize> {
+        doc_comment
+            .match_indices(element_name)
+            .filter(|(idx, _)| {
+                Self::starts_word(doc_comment, *idx)
+                    && !doc_comment[idx + element_name.len()..]
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    fn get_tag_contents(doc_comment: &'f str, start_element: usize) -> &'f str {
+        let end_element = match Self::find_next_tag(&doc_comment[start_element..]) {
             Some(loc) => start_element + loc,
             None => doc_comment.len(),
         };
 
-        Some(doc_comment[start_element..end_element].trim())
+        doc_comment[start_element..end_element].trim()
     }
 
-    pub fn get_params(doc_comment: &'f str) -> Result<Option<Vec<FunctionParam>>> {
+    pub fn get_single_doc_tag(doc_comment: &'f str, element_name: &str) -> Option<&'f str> {
+        let start_element = *Self::find_doc_tags(doc_comment, element_name).first()?;
+
+        Some(Self::get_tag_contents(
+            doc_comment,
+            start_element + element_name.len(),
+        ))
+    }
+
+    pub fn get_all_doc_tags(doc_comment: &'f str, element_name: &str) -> Vec<&'f str> {
+        Self::find_doc_tags(doc_comment, element_name)
+            .into_iter()
+            .map(|start_element| {
+                Self::get_tag_contents(doc_comment, start_element + element_name.len())
+            })
+            .collect()
+    }
+
+    pub fn get_params(doc_comment: &'f str) -> Result<Option<Vec<FunctionParam<'f>>>> {
         let mut start_param = doc_comment.find("@param");
 
         if start_param.is_none() {
@@ -116,19 +368,16 @@ impl<'f> FunctionDocParser<'f> {
         Ok(Some(all_params))
     }
 
-    pub fn get_return(doc_comment: &'f str) -> Result<Option<FunctionReturn>> {
-        let start_return = doc_comment.find("@return");
-
-        if start_return.is_none() {
-            // There is no return statement
-            return Ok(None);
-        }
-
-        let start_return = start_return.unwrap();
+    pub fn get_return(doc_comment: &'f str) -> Result<Option<FunctionReturn<'f>>> {
+        let start_return = match Self::find_doc_tags(doc_comment, "@return").first() {
+            Some(start_return) => start_return + "@return".len(),
+            None => return Ok(None), // There is no return statement
+        };
 
-        Ok(Some(FunctionReturn::new(
-            doc_comment[start_return..doc_comment.len()].trim(),
-        )?))
+        Ok(Some(FunctionReturn::new(Self::get_tag_contents(
+            doc_comment,
+            start_return,
+        ))?))
     }
 }
 
@@ -156,7 +405,7 @@ impl<'p> FunctionParam<'p> {
         let the_rest = &param_string[end_type_declaration + 1..];
         let mut has_seen_char = false;
 
-        for (idx, c) in the_rest.chars().enumerate() {
+        for (idx, c) in the_rest.char_indices() {
             match (c.is_whitespace(), has_seen_char) {
                 (true, true) => {
                     end_name += idx;
@@ -170,6 +419,11 @@ impl<'p> FunctionParam<'p> {
             }
         }
 
+        if has_seen_char && end_name <= start_name {
+            // The name is the last thing in the param statement
+            end_name = param_string.len();
+        }
+
         let name = &param_string[start_name..end_name];
 
         let description =
@@ -219,6 +473,75 @@ impl<'r> FunctionReturn<'r> {
     }
 }
 
+#[derive(Clone, Default, Debug, Eq, PartialEq)]
+pub struct FunctionThrows<'t> {
+    pub sqlstate: &'t str, // Either the SQLSTATE code or the condition name
+    pub description: Option<&'t str>,
+}
+
+impl<'t> FunctionThrows<'t> {
+    fn new(throws_string: &'t str) -> Result<Self> {
+        let start_sqlstate = throws_string
+            .find('{')
+            .context("There was no SQLSTATE declaration for the given throws statement")?
+            + 1;
+        let end_sqlstate = throws_string
+            .find('}')
+            .context("Badly formatted SQLSTATE declaration for throws statement")?;
+
+        let description = throws_string[end_sqlstate + 1..].trim();
+
+        Ok(Self {
+            sqlstate: throws_string[start_sqlstate..end_sqlstate].trim(),
+            description: if description.is_empty() {
+                None
+            } else {
+                Some(description)
+            },
+        })
+    }
+}
+
+#[derive(Clone, Default, Debug, Eq, PartialEq)]
+pub struct FunctionSee<'s> {
+    pub target: &'s str, // A function (optionally schema qualified) or a url
+    pub description: Option<&'s str>,
+}
+
+impl<'s> FunctionSee<'s> {
+    fn new(see_string: &'s str) -> Self {
+        match see_string.split_once(char::is_whitespace) {
+            Some((target, description)) => Self {
+                target,
+                description: Some(description.trim()),
+            },
+            None => Self {
+                target: see_string,
+                description: None,
+            },
+        }
+    }
+
+    // Get the schema (if it was qualified) and name of the function being referenced, or None if
+    // the target is a url
+    pub fn function_path(&self) -> Option<(Option<&'s str>, &'s str)> {
+        if self.target.contains("://") {
+            return None;
+        }
+
+        // Any arguments given to pick out an overload are ignored as each function has one page
+        let path = match self.target.find('(') {
+            Some(args_start) => &self.target[..args_start],
+            None => self.target,
+        };
+
+        Some(match path.rsplit_once('.') {
+            Some((schema, function_name)) => (Some(schema), function_name),
+            None => (None, path),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -471,7 +794,7 @@ THIS IS JUST HERE FOR A BUFFER
             description: "This is the function description",
             author: Some("Homer Simpson"),
             date: Some("01/02/1234"),
-            example: Some("SELECT public.concatenating('Hello ', 'World');"),
+            examples: vec!["SELECT public.concatenating('Hello ', 'World');"],
             params: Some(vec![
                 FunctionParam {
                     name: "var1",
@@ -488,6 +811,7 @@ THIS IS JUST HERE FOR A BUFFER
                 return_type: "TEXT",
                 description: Some("var1 and var2 concatenated together"),
             }),
+            ..Default::default()
         };
 
         assert_eq!(
@@ -495,4 +819,160 @@ THIS IS JUST HERE FOR A BUFFER
             FunctionDocParser::new("public", "concatenating", input).unwrap().unwrap()
         );
     }
+
+    #[test]
+    fn new_doc_tags_work() {
+        let input = r#"
+    CREATE OR REPLACE FUNCTION public.find_people(filter jsonb)
+     RETURNS SETOF people
+     LANGUAGE sql
+    AS $function$
+    	/**
+    		Finds the people matching the filter
+
+            @since 1.2.0
+            @deprecated Use search_people instead
+            @example SELECT public.find_people('{"age": 42}');
+            @example SELECT public.find_people('{}');
+
+    		@param {JSONB} filter The properties the people must have
+
+    		@return {SETOF people} The matching people
+            @throws {22023} The filter is not an object
+            @throws {P0001}
+            @see other.search_people(jsonb) The replacement
+            @see https://www.postgresql.org/docs/current/functions-json.html
+    	*/
+    	SELECT * FROM people WHERE to_jsonb(people) @> filter;
+    $function$"#;
+
+        let function_info = FunctionDocParser::new("public", "find_people", input)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(function_info.since, Some("1.2.0"));
+        assert_eq!(function_info.deprecated, Some("Use search_people instead"));
+        assert_eq!(
+            function_info.examples,
+            vec![
+                r#"SELECT public.find_people('{"age": 42}');"#,
+                "SELECT public.find_people('{}');"
+            ]
+        );
+        assert_eq!(
+            function_info.returns,
+            Some(FunctionReturn {
+                return_type: "SETOF people",
+                description: Some("The matching people"),
+            })
+        );
+        assert_eq!(
+            function_info.throws,
+            vec![
+                FunctionThrows {
+                    sqlstate: "22023",
+                    description: Some("The filter is not an object"),
+                },
+                FunctionThrows {
+                    sqlstate: "P0001",
+                    description: None,
+                },
+            ]
+        );
+        assert_eq!(
+            function_info
+                .see
+                .iter()
+                .map(FunctionSee::function_path)
+                .collect::<Vec<_>>(),
+            vec![Some((Some("other"), "search_people")), None]
+        );
+        assert_eq!(function_info.see[0].description, Some("The replacement"));
+    }
+
+    #[test]
+    fn line_comment_parser_works() {
+        let input = r#"
+CREATE OR REPLACE FUNCTION public.concat(var1 text, var2 text)
+ RETURNS text
+ LANGUAGE plpgsql
+AS $function$
+BEGIN
+    -- Not part of the docs
+
+    ---------------------------
+    -- This is the function description
+    --
+    -- @param {TEXT} var1 The first part of the output
+    -- @param {TEXT} var2 The second part of the output
+    ---------------------------
+    return var1 || var2; -- Not part of the docs either
+END
+$function$"#;
+
+        let doc_comment = LineCommentParser {}
+            .find_doc_comment("concat(text, text)", input)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            doc_comment,
+            "This is the function description\n\n@param {TEXT} var1 The first part of the output\n@param {TEXT} var2 The second part of the output"
+        );
+
+        let function_info =
+            FunctionDocParser::from_doc_comment("public", "concat", input, &doc_comment).unwrap();
+        assert_eq!(function_info.function_full_name, "concat(var1 text, var2 text)");
+        assert_eq!(function_info.description, "This is the function description");
+        assert_eq!(function_info.params.unwrap().len(), 2);
+
+        assert_eq!(
+            LineCommentParser {}
+                .find_doc_comment("concat(text, text)", "-- No tags here\nSELECT 1;")
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn to_comment_text_works() {
+        let doc_comment = r#"This is the function description
+
+            @author Homer Simpson
+            @param {TEXT} var1 The first part of the output
+            @param {TEXT} var2
+            @return {TEXT} var1 and var2 concatenated together
+            @example SELECT public.concat('Hello ', 'World');"#;
+
+        let function_info = FunctionDocParser::from_doc_comment(
+            "public",
+            "concat",
+            "CREATE FUNCTION public.concat(var1 text, var2 text)",
+            doc_comment,
+        )
+        .unwrap();
+
+        let comment_text = function_info.to_comment_text();
+        assert_eq!(
+            comment_text,
+            r#"This is the function description
+
+@author Homer Simpson
+@param {TEXT} var1 The first part of the output
+@param {TEXT} var2
+@return {TEXT} var1 and var2 concatenated together
+@example SELECT public.concat('Hello ', 'World');"#
+        );
+
+        // The comment should parse back into the same docs
+        assert_eq!(
+            FunctionDocParser::from_doc_comment(
+                "public",
+                "concat",
+                "CREATE FUNCTION public.concat(var1 text, var2 text)",
+                &comment_text,
+            )
+            .unwrap(),
+            function_info
+        );
+    }
 }
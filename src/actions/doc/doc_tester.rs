@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use crate::actions::unit_test::test_config_manager::TestConfig;
+
+use super::doc_parser::FunctionDocParser;
+
+// The column the result of an example is compared on when it has an expected value
+const EXAMPLE_RESULT_COLUMN: &str = "result";
+
+// An @example split into the query to run and the value it should return (given after =>)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocExample {
+    pub query: String,
+    pub expected: Option<String>,
+}
+
+impl DocExample {
+    // The positions of the ; characters that are outside of quoted strings and identifiers
+    fn statement_ends(example: &str) -> Vec<usize> {
+        let mut quote = None;
+        let mut statement_ends = Vec::new();
+        for (idx, c) in example.char_indices() {
+            match (quote, c) {
+                (None, '\'' | '"') => quote = Some(c),
+                (Some(open), _) if c == open => quote = None,
+                (None, ';') => statement_ends.push(idx),
+                _ => {}
+            }
+        }
+
+        statement_ends
+    }
+
+    // The expected value comes after the first ; that ends a statement and is followed by a =>, so
+    // that => can still be used for named arguments and ; within quotes or the expected value
+    pub fn parse(example: &str) -> Self {
+        let expected = Self::statement_ends(example)
+            .into_iter()
+            .find_map(|query_end| {
+                example[query_end + 1..]
+                    .trim()
+                    .strip_prefix("=>")
+                    .map(|expected| (query_end, expected.trim()))
+            });
+
+        match expected {
+            Some((query_end, expected)) => {
+                let expected = match expected.len() > 1
+                    && expected.starts_with('\'')
+                    && expected.ends_with('\'')
+                {
+                    true => expected[1..expected.len() - 1].replace("''", "'"),
+                    false => expected.to_string(),
+                };
+
+                Self {
+                    query: example[..query_end].trim().to_string(),
+                    expected: Some(expected),
+                }
+            }
+            None => Self {
+                query: example.trim().to_string(),
+                expected: None,
+            },
+        }
+    }
+
+    // Build the unit test that runs the example. The query is wrapped when there is an expected
+    // value so that the single column it returns can be compared whatever it is named
+    pub fn to_test_config(&self, test_name: String) -> TestConfig {
+        let (query, expected_output) = match &self.expected {
+            Some(expected) => (
+                format!(
+                    "SELECT * FROM ({}) AS example_result({})",
+                    self.query.trim_end_matches(';'),
+                    EXAMPLE_RESULT_COLUMN
+                ),
+                Some(vec![HashMap::from([(
+                    EXAMPLE_RESULT_COLUMN.to_string(),
+                    expected.clone(),
+                )])]),
+            ),
+            None => (self.query.clone(), None),
+        };
+
+        TestConfig {
+            name: test_name,
+            query,
+            expected_output,
+            expected_side_effect: None,
+            tags: None,
+            property: None,
+        }
+    }
+}
+
+// Turns the @example tags of functions into unit tests
+pub struct DocTester {}
+
+impl DocTester {
+    pub fn get_test_configs(function_info: &FunctionDocParser<'_>) -> Vec<TestConfig> {
+        function_info
+            .examples
+            .iter()
+            .enumerate()
+            .map(|(idx, example)| {
+                DocExample::parse(example).to_test_config(format!(
+                    "{} @example {}",
+                    function_info.function_full_name,
+                    idx + 1
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_example_works() {
+        assert_eq!(
+            DocExample::parse("SELECT public.concat(var1 => 'a', var2 => 'b');"),
+            DocExample {
+                query: "SELECT public.concat(var1 => 'a', var2 => 'b');".to_string(),
+                expected: None,
+            }
+        );
+        assert_eq!(
+            DocExample::parse("SELECT public.concat(var1 => 'a', var2 => 'b'); => ab"),
+            DocExample {
+                query: "SELECT public.concat(var1 => 'a', var2 => 'b')".to_string(),
+                expected: Some("ab".to_string()),
+            }
+        );
+        assert_eq!(
+            DocExample::parse("SELECT ' it''s '; => ' it''s '").expected,
+            Some(" it's ".to_string())
+        );
+        assert_eq!(
+            DocExample::parse("SELECT public.split('a;b', ';'); => {a,b}"),
+            DocExample {
+                query: "SELECT public.split('a;b', ';')".to_string(),
+                expected: Some("{a,b}".to_string()),
+            }
+        );
+        assert_eq!(
+            DocExample::parse("SELECT public.join(ARRAY['a', 'b'], ';'); => a;b"),
+            DocExample {
+                query: "SELECT public.join(ARRAY['a', 'b'], ';')".to_string(),
+                expected: Some("a;b".to_string()),
+            }
+        );
+        assert_eq!(
+            DocExample::parse("SELECT 'a => b;';"),
+            DocExample {
+                query: "SELECT 'a => b;';".to_string(),
+                expected: None,
+            }
+        );
+    }
+
+    #[test]
+    fn to_test_config_works() {
+        let test = DocExample::parse("SELECT 1 + 1; => 2").to_test_config("adding".to_string());
+
+        assert_eq!(test.query, "SELECT * FROM (SELECT 1 + 1) AS example_result(result)");
+        assert_eq!(
+            test.expected_output,
+            Some(vec![HashMap::from([("result".to_string(), "2".to_string())])])
+        );
+    }
+}
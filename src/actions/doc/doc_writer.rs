@@ -2,18 +2,43 @@ use anyhow::{Result, Context};
 use colored::Colorize;
 use tokio::io::AsyncWriteExt;
 
-use super::doc_parser::FunctionDocParser;
+use super::{
+    call_graph::{CallGraph, FunctionRef},
+    doc_parser::FunctionDocParser,
+};
+
+pub const MARKDOWN_DOC_ROOT: &str = "./documentation/markdown";
 
 pub struct FunctionDocWriter {}
 
 impl FunctionDocWriter {
+    fn single_line(text: &str) -> String {
+        text.replace("\r\n", "").replace('\n', "")
+    }
+
+    // Link to the docs of a function from the docs of another function in from_schema
+    fn function_link(from_schema: &str, schema: &str, function_name: &str) -> String {
+        match from_schema == schema {
+            true => format!("{}.md", function_name),
+            false => format!("../{}/{}.md", schema, function_name),
+        }
+    }
+
+    // Start a section, making sure it is separated from whatever came before it
+    fn start_section(file_content: &mut String, heading: &str) {
+        while !file_content.ends_with("\n\n") {
+            file_content.push('\n');
+        }
+        file_content.push_str(&format!("### {}\n\n", heading));
+    }
+
     pub async fn write_doc_to_file(function_info: &FunctionDocParser<'_>) -> Result<()> {
         // If the function docs already exist (in the case of an overload)
         // then just append to the fil;e rather than creating from scrathc
 
         let file_name = &format!(
-            "./documentation/{}/{}.md",
-            function_info.schema, function_info.function_name
+            "{}/{}/{}.md",
+            MARKDOWN_DOC_ROOT, function_info.schema, function_info.function_name
         );
         let file_path = std::path::Path::new(&file_name);
 
@@ -34,12 +59,27 @@ impl FunctionDocWriter {
 
         file_content.push_str(&format!("## {}\n", function_info.function_full_name));
 
+        if let Some(reason) = function_info.deprecated {
+            match reason {
+                "" => file_content.push_str("> **Deprecated**\n\n"),
+                reason => file_content.push_str(&format!("> **Deprecated:** {}\n\n", reason)),
+            }
+        }
+
         if let Some(author) = function_info.author {
             file_content.push_str(&format!("- Author: {}\n", author));
         }
 
+        if let Some(since) = function_info.since {
+            file_content.push_str(&format!("- Since: {}\n", since));
+        }
+
         if let Some(date) = function_info.date {
-            file_content.push_str(&format!("- Date: {}\n\n", date));
+            file_content.push_str(&format!("- Date: {}\n", date));
+        }
+
+        if !file_content.ends_with("\n\n") {
+            file_content.push('\n');
         }
 
         file_content.push_str(&format!(
@@ -123,15 +163,115 @@ impl FunctionDocWriter {
             ));
         }
 
-        if let Some(example) = function_info.example {
-            file_content.push_str("### Example\n\n");
-            file_content.push_str(&format!("```sql\n{}\n```\n\n", example));
+        if !function_info.throws.is_empty() {
+            Self::start_section(&mut file_content, "Throws");
+
+            // Get the text width required
+            let mut max_sqlstate_width = 8; // "SQLSTATE".len()
+            let mut max_description_width = 11; // "Description".len()
+            for throws in &function_info.throws {
+                max_sqlstate_width = max_sqlstate_width.max(throws.sqlstate.len());
+                max_description_width = max_description_width
+                    .max(Self::single_line(throws.description.unwrap_or_default()).len());
+            }
+
+            file_content.push_str(&format!(
+                "| {:max_sqlstate_width$} | {:max_description_width$} |\n",
+                "SQLSTATE", "Description"
+            ));
+            file_content.push_str(&format!(
+                "| {:-<max_sqlstate_width$} | {:-<max_description_width$} |\n",
+                "", ""
+            ));
+            for throws in &function_info.throws {
+                file_content.push_str(&format!(
+                    "| {:max_sqlstate_width$} | {:max_description_width$} |\n",
+                    throws.sqlstate,
+                    Self::single_line(throws.description.unwrap_or_default())
+                ));
+            }
+        }
+
+        if !function_info.examples.is_empty() {
+            Self::start_section(
+                &mut file_content,
+                if function_info.examples.len() == 1 {
+                    "Example"
+                } else {
+                    "Examples"
+                },
+            );
+            for example in &function_info.examples {
+                file_content.push_str(&format!("```sql\n{}\n```\n\n", example));
+            }
+        }
+
+        if !function_info.see.is_empty() {
+            Self::start_section(&mut file_content, "See Also");
+            for see in &function_info.see {
+                let link = match see.function_path() {
+                    Some((schema, function_name)) => Self::function_link(
+                        function_info.schema,
+                        schema.unwrap_or(function_info.schema),
+                        function_name,
+                    ),
+                    None => see.target.to_string(),
+                };
+                file_content.push_str(&format!("- [{}](<{}>)", see.target, link));
+                if let Some(description) = see.description {
+                    file_content.push_str(&format!(" {}", Self::single_line(description)));
+                }
+                file_content.push('\n');
+            }
+            file_content.push('\n');
         }
 
         file.write_all(file_content.as_bytes()).await?;
         println!("\t{} Docs Generated", function_info.function_name.bold().magenta());
         Ok(())
     }
+
+    // Append the functions called by and calling the function, which cover all of its overloads
+    pub async fn write_call_graph_to_file(
+        function: &FunctionRef,
+        call_graph: &CallGraph,
+    ) -> Result<()> {
+        let mut file_content = String::new();
+        for (heading, functions) in [
+            ("Calls", call_graph.calls(function)),
+            ("Called by", call_graph.called_by(function)),
+        ] {
+            if functions.is_empty() {
+                continue;
+            }
+
+            file_content.push_str(&format!("## {}\n\n", heading));
+            for other in functions {
+                file_content.push_str(&format!(
+                    "- [{}](<{}>)\n",
+                    other,
+                    Self::function_link(&function.schema, &other.schema, &other.name)
+                ));
+            }
+            file_content.push('\n');
+        }
+
+        if file_content.is_empty() {
+            return Ok(());
+        }
+
+        let file_name = format!(
+            "{}/{}/{}.md",
+            MARKDOWN_DOC_ROOT, function.schema, function.name
+        );
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(file_name)
+            .await?;
+        file.write_all(file_content.as_bytes()).await?;
+
+        Ok(())
+    }
 }
 
 // Example:
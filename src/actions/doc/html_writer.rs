@@ -0,0 +1,811 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+
+use super::{
+    call_graph::{CallGraph, FunctionRef},
+    ddl_doc_writer::{DocSection, ObjectDoc},
+    doc_parser::FunctionDocParser,
+};
+
+pub const HTML_DOC_ROOT: &str = "./documentation/html";
+
+const STYLE: &str = r#"
+body { margin: 0; font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; color: #24292f; line-height: 1.5; }
+header { background: #2f3e4e; color: #fff; padding: 0.75rem 1.5rem; display: flex; gap: 1.5rem; align-items: center; flex-wrap: wrap; }
+header a { color: #fff; }
+header input { padding: 0.3rem 0.5rem; min-width: 18rem; border-radius: 4px; border: none; }
+#search-results { position: absolute; top: 3rem; background: #fff; border: 1px solid #d0d7de; list-style: none; margin: 0; padding: 0.5rem 1rem; max-height: 60vh; overflow-y: auto; }
+#search-results:empty { display: none; }
+.layout { display: flex; }
+nav { min-width: 14rem; padding: 1rem 1.5rem; border-right: 1px solid #d0d7de; }
+nav ul { list-style: none; padding: 0; }
+main { padding: 1rem 2rem; max-width: 60rem; flex-grow: 1; }
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+.description { white-space: pre-wrap; }
+.meta { color: #57606a; }
+.deprecated { background: #fff8c5; border-left: 4px solid #d4a72c; padding: 0.5rem 0.75rem; }
+.anchor { margin-left: 0.5rem; color: #8c959f; }
+table { border-collapse: collapse; margin-bottom: 1rem; }
+th, td { border: 1px solid #d0d7de; padding: 0.3rem 0.75rem; text-align: left; vertical-align: top; }
+pre { background: #f6f8fa; padding: 0.75rem; overflow-x: auto; }
+section.overload { border-top: 1px solid #d0d7de; margin-top: 1.5rem; }
+"#;
+
+const SEARCH_SCRIPT: &str = r#"
+(function () {
+    var input = document.getElementById('search');
+    var results = document.getElementById('search-results');
+    var root = document.body.getAttribute('data-root');
+    input.addEventListener('input', function () {
+        var terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+        results.innerHTML = '';
+        if (!terms.length) {
+            return;
+        }
+        (window.TUSK_SEARCH || []).filter(function (entry) {
+            var text = (entry.schema + '.' + entry.full_name + ' ' + entry.description).toLowerCase();
+            return terms.every(function (term) { return text.indexOf(term) !== -1; });
+        }).slice(0, 50).forEach(function (entry) {
+            var item = document.createElement('li');
+            var link = document.createElement('a');
+            link.href = root + entry.url;
+            link.textContent = entry.schema + '.' + entry.full_name;
+            item.appendChild(link);
+            results.appendChild(item);
+        });
+    });
+})();
+"#;
+
+// An entry in the client side search index
+#[derive(Debug, Serialize)]
+struct SearchEntry<'a> {
+    schema: &'a str,
+    name: &'a str,
+    full_name: &'a str,
+    description: &'a str,
+    url: String, // Relative to the root of the html docs
+}
+
+// Everything documented within a schema, sorted in the order it is listed
+pub struct SchemaDocs<'a, 'f> {
+    schema: &'a str,
+    functions: BTreeMap<&'a str, Vec<&'a FunctionDocParser<'f>>>,
+    objects: Vec<&'a ObjectDoc>,
+    call_graph: Option<&'a CallGraph>,
+}
+
+impl<'a, 'f> SchemaDocs<'a, 'f> {
+    pub fn new(
+        schema: &'a str,
+        function_infos: &'a [FunctionDocParser<'f>],
+        objects: &'a [ObjectDoc],
+    ) -> Self {
+        let mut functions: BTreeMap<&str, Vec<&FunctionDocParser<'_>>> = BTreeMap::new();
+        for function_info in function_infos {
+            functions
+                .entry(function_info.function_name)
+                .or_default()
+                .push(function_info);
+        }
+        for overloads in functions.values_mut() {
+            overloads.sort_by_key(|overload| overload.function_full_name);
+        }
+
+        let mut objects: Vec<&ObjectDoc> = objects.iter().collect();
+        objects.sort_by(|a, b| (a.kind.dir(), &a.name).cmp(&(b.kind.dir(), &b.name)));
+
+        Self {
+            schema,
+            functions,
+            objects,
+            call_graph: None,
+        }
+    }
+
+    pub fn with_call_graph(mut self, call_graph: &'a CallGraph) -> Self {
+        self.call_graph = Some(call_graph);
+        self
+    }
+}
+
+// Writes the parsed docs as a self contained static html site
+pub struct HtmlDocWriter {}
+
+impl HtmlDocWriter {
+    pub fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    }
+
+    // Percent encode anything that could be misinterpreted within a relative url
+    pub fn encode_url_component(text: &str) -> String {
+        text.bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    }
+
+    // The id used to link directly to an overload of a function. Array brackets are spelt out so
+    // that an overload taking an array has a different id to the one taking a single value
+    pub fn overload_anchor(function_full_name: &str) -> String {
+        let mut anchor = String::new();
+        for c in function_full_name
+            .to_lowercase()
+            .replace("[]", " array")
+            .chars()
+        {
+            if c.is_alphanumeric() || c == '_' {
+                anchor.push(c);
+            } else if !anchor.is_empty() && !anchor.ends_with('-') {
+                anchor.push('-');
+            }
+        }
+
+        anchor.trim_end_matches('-').to_string()
+    }
+
+    // The file name is the same as its url so that it works when opened straight from the disk
+    fn page_file_name(name: &str) -> String {
+        format!("{}.html", Self::encode_url_component(name).replace('%', "_"))
+    }
+
+    // Function pages are in their own directory, like the pages of tables, views and data types, so
+    // that a function can't share its name with the schema's index page
+    fn function_page_name(function_name: &str) -> String {
+        format!("functions/{}", Self::page_file_name(function_name))
+    }
+
+    fn render_page(
+        title: &str,
+        root: &str,
+        search_scripts: &[String],
+        nav: &str,
+        main: &str,
+    ) -> String {
+        let scripts: String = search_scripts
+            .iter()
+            .map(|script| format!("<script src=\"{}\"></script>\n", script))
+            .collect();
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>{STYLE}</style>
+</head>
+<body data-root="{root}">
+<header>
+<a href="{root}index.html"><strong>Database Documentation</strong></a>
+<input id="search" type="search" placeholder="Search..." autocomplete="off">
+<ul id="search-results"></ul>
+</header>
+<div class="layout">
+<nav>
+{nav}
+</nav>
+<main>
+{main}
+</main>
+</div>
+{scripts}<script>{SEARCH_SCRIPT}</script>
+</body>
+</html>
+"#,
+            title = Self::escape_html(title),
+        )
+    }
+
+    fn object_page_name(object: &ObjectDoc) -> String {
+        format!(
+            "{}/{}",
+            object.kind.dir(),
+            Self::page_file_name(&object.name)
+        )
+    }
+
+    // The list of functions, tables, views and data types in the schema that is shown beside every
+    // page of the schema
+    fn render_schema_nav(schema: &SchemaDocs, page_prefix: &str) -> String {
+        let mut nav = format!(
+            "<h3><a href=\"{}index.html\">{}</a></h3>\n",
+            page_prefix,
+            Self::escape_html(schema.schema)
+        );
+
+        if !schema.functions.is_empty() {
+            nav.push_str("<h4>Functions</h4>\n<ul>\n");
+            for function_name in schema.functions.keys() {
+                nav.push_str(&format!(
+                    "<li><a href=\"{}{}\">{}</a></li>\n",
+                    page_prefix,
+                    Self::function_page_name(function_name),
+                    Self::escape_html(function_name)
+                ));
+            }
+            nav.push_str("</ul>\n");
+        }
+
+        let mut current_dir = "";
+        for object in &schema.objects {
+            if object.kind.dir() != current_dir {
+                if !current_dir.is_empty() {
+                    nav.push_str("</ul>\n");
+                }
+                current_dir = object.kind.dir();
+                nav.push_str(&format!(
+                    "<h4>{}</h4>\n<ul>\n",
+                    Self::dir_heading(current_dir)
+                ));
+            }
+            nav.push_str(&format!(
+                "<li><a href=\"{}{}\">{}</a></li>\n",
+                page_prefix,
+                Self::object_page_name(object),
+                Self::escape_html(&object.name)
+            ));
+        }
+        if !current_dir.is_empty() {
+            nav.push_str("</ul>\n");
+        }
+
+        nav
+    }
+
+    fn dir_heading(dir: &str) -> &'static str {
+        match dir {
+            "tables" => "Tables",
+            "views" => "Views",
+            _ => "Data Types",
+        }
+    }
+
+    // Link to the page of a function from the page of another function in from_schema
+    fn function_link(from_schema: &str, schema: &str, function_name: &str) -> String {
+        match from_schema == schema {
+            true => Self::page_file_name(function_name),
+            false => format!(
+                "../../{}/{}",
+                Self::encode_url_component(schema),
+                Self::function_page_name(function_name)
+            ),
+        }
+    }
+
+    fn render_calls(schema: &str, function_name: &str, call_graph: &CallGraph) -> String {
+        let function = FunctionRef {
+            schema: schema.to_string(),
+            name: function_name.to_string(),
+        };
+
+        let mut section = String::new();
+        for (heading, functions) in [
+            ("Calls", call_graph.calls(&function)),
+            ("Called by", call_graph.called_by(&function)),
+        ] {
+            if functions.is_empty() {
+                continue;
+            }
+
+            section.push_str(&format!("<h2>{}</h2>\n<ul>\n", heading));
+            for other in functions {
+                section.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a></li>\n",
+                    Self::escape_html(&Self::function_link(schema, &other.schema, &other.name)),
+                    Self::escape_html(&other.to_string())
+                ));
+            }
+            section.push_str("</ul>\n");
+        }
+
+        section
+    }
+
+    fn render_overload(function_info: &FunctionDocParser<'_>) -> String {
+        let anchor = Self::overload_anchor(function_info.function_full_name);
+        let mut section = format!(
+            "<section class=\"overload\" id=\"{}\">\n<h2>{}<a class=\"anchor\" href=\"#{}\">#</a></h2>\n",
+            anchor,
+            Self::escape_html(function_info.function_full_name),
+            anchor
+        );
+
+        if let Some(reason) = function_info.deprecated {
+            section.push_str(&match reason {
+                "" => String::from("<p class=\"deprecated\"><strong>Deprecated</strong></p>\n"),
+                reason => format!(
+                    "<p class=\"deprecated\"><strong>Deprecated:</strong> {}</p>\n",
+                    Self::escape_html(reason)
+                ),
+            });
+        }
+
+        let mut meta = vec![];
+        if let Some(author) = function_info.author {
+            meta.push(format!("Author: {}", Self::escape_html(author)));
+        }
+        if let Some(since) = function_info.since {
+            meta.push(format!("Since: {}", Self::escape_html(since)));
+        }
+        if let Some(date) = function_info.date {
+            meta.push(format!("Date: {}", Self::escape_html(date)));
+        }
+        if !meta.is_empty() {
+            section.push_str(&format!("<p class=\"meta\">{}</p>\n", meta.join(" | ")));
+        }
+
+        section.push_str(&format!(
+            "<h3>Description</h3>\n<p class=\"description\">{}</p>\n",
+            Self::escape_html(function_info.description)
+        ));
+
+        if let Some(params) = &function_info.params {
+            section.push_str("<h3>Arguments</h3>\n<table>\n<tr><th>Name</th><th>Type</th><th>Description</th></tr>\n");
+            for param in params {
+                section.push_str(&format!(
+                    "<tr><td>{}</td><td><code>{}</code></td><td>{}</td></tr>\n",
+                    Self::escape_html(param.name),
+                    Self::escape_html(param.param_type),
+                    Self::escape_html(param.description.unwrap_or_default())
+                ));
+            }
+            section.push_str("</table>\n");
+        }
+
+        if let Some(return_val) = &function_info.returns {
+            section.push_str(&format!(
+                "<h3>Return Type</h3>\n<table>\n<tr><th>Type</th><th>Description</th></tr>\n<tr><td><code>{}</code></td><td>{}</td></tr>\n</table>\n",
+                Self::escape_html(return_val.return_type),
+                Self::escape_html(return_val.description.unwrap_or_default())
+            ));
+        }
+
+        if !function_info.throws.is_empty() {
+            section.push_str("<h3>Throws</h3>\n<table>\n<tr><th>SQLSTATE</th><th>Description</th></tr>\n");
+            for throws in &function_info.throws {
+                section.push_str(&format!(
+                    "<tr><td><code>{}</code></td><td>{}</td></tr>\n",
+                    Self::escape_html(throws.sqlstate),
+                    Self::escape_html(throws.description.unwrap_or_default())
+                ));
+            }
+            section.push_str("</table>\n");
+        }
+
+        if !function_info.examples.is_empty() {
+            section.push_str(match function_info.examples.len() {
+                1 => "<h3>Example</h3>\n",
+                _ => "<h3>Examples</h3>\n",
+            });
+            for example in &function_info.examples {
+                section.push_str(&format!(
+                    "<pre><code>{}</code></pre>\n",
+                    Self::escape_html(example)
+                ));
+            }
+        }
+
+        if !function_info.see.is_empty() {
+            section.push_str("<h3>See Also</h3>\n<ul>\n");
+            for see in &function_info.see {
+                let link = match see.function_path() {
+                    Some((schema, function_name)) => Self::function_link(
+                        function_info.schema,
+                        schema.unwrap_or(function_info.schema),
+                        function_name,
+                    ),
+                    None => see.target.to_string(),
+                };
+                section.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a> {}</li>\n",
+                    Self::escape_html(&link),
+                    Self::escape_html(see.target),
+                    Self::escape_html(see.description.unwrap_or_default())
+                ));
+            }
+            section.push_str("</ul>\n");
+        }
+
+        section.push_str("</section>");
+        section
+    }
+
+    pub fn render_function_page(schema: &SchemaDocs, function_name: &str) -> String {
+        let overloads = schema
+            .functions
+            .get(function_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut main = format!(
+            "<p class=\"meta\"><a href=\"../../index.html\">All schemas</a> / <a href=\"../index.html\">{}</a></p>\n<h1>{}</h1>\n",
+            Self::escape_html(schema.schema),
+            Self::escape_html(function_name)
+        );
+
+        if overloads.len() > 1 {
+            main.push_str("<p>Overloads:</p>\n<ul>\n");
+            for overload in overloads {
+                main.push_str(&format!(
+                    "<li><a href=\"#{}\">{}</a></li>\n",
+                    Self::overload_anchor(overload.function_full_name),
+                    Self::escape_html(overload.function_full_name)
+                ));
+            }
+            main.push_str("</ul>\n");
+        }
+
+        for overload in overloads {
+            main.push_str(&Self::render_overload(overload));
+            main.push('\n');
+        }
+
+        if let Some(call_graph) = schema.call_graph {
+            main.push_str(&Self::render_calls(schema.schema, function_name, call_graph));
+        }
+
+        Self::render_page(
+            &format!("{}.{}", schema.schema, function_name),
+            "../../",
+            &[String::from("../search_index.js")],
+            &Self::render_schema_nav(schema, "../"),
+            &main,
+        )
+    }
+
+    pub fn render_object_page(schema: &SchemaDocs, object: &ObjectDoc) -> String {
+        let mut main = format!(
+            "<p class=\"meta\"><a href=\"../../index.html\">All schemas</a> / <a href=\"../index.html\">{}</a></p>\n<h1>{}</h1>\n<p class=\"meta\">{}</p>\n",
+            Self::escape_html(schema.schema),
+            Self::escape_html(&object.name),
+            object.kind.label()
+        );
+
+        if let Some(description) = &object.description {
+            main.push_str(&format!(
+                "<h3>Description</h3>\n<p class=\"description\">{}</p>\n",
+                Self::escape_html(description)
+            ));
+        }
+
+        for section in &object.sections {
+            match section {
+                DocSection::Table {
+                    heading,
+                    headers,
+                    rows,
+                } => {
+                    main.push_str(&format!("<h3>{}</h3>\n<table>\n<tr>", heading));
+                    for header in headers {
+                        main.push_str(&format!("<th>{}</th>", header));
+                    }
+                    main.push_str("</tr>\n");
+                    for row in rows {
+                        main.push_str("<tr>");
+                        for cell in row {
+                            main.push_str(&format!("<td>{}</td>", Self::escape_html(cell)));
+                        }
+                        main.push_str("</tr>\n");
+                    }
+                    main.push_str("</table>\n");
+                }
+                DocSection::Code { heading, code } => main.push_str(&format!(
+                    "<h3>{}</h3>\n<pre><code>{}</code></pre>\n",
+                    heading,
+                    Self::escape_html(code)
+                )),
+            }
+        }
+
+        Self::render_page(
+            &format!("{}.{}", schema.schema, object.name),
+            "../../",
+            &[String::from("../search_index.js")],
+            &Self::render_schema_nav(schema, "../"),
+            &main,
+        )
+    }
+
+    pub fn render_schema_index(schema: &SchemaDocs) -> String {
+        let mut main = format!(
+            "<p class=\"meta\"><a href=\"../index.html\">All schemas</a></p>\n<h1>{}</h1>\n",
+            Self::escape_html(schema.schema)
+        );
+
+        if !schema.functions.is_empty() {
+            main.push_str(
+                "<h2>Functions</h2>\n<table>\n<tr><th>Function</th><th>Description</th></tr>\n",
+            );
+            for (function_name, overloads) in &schema.functions {
+                for overload in overloads {
+                    main.push_str(&format!(
+                        "<tr><td><a href=\"{}#{}\">{}</a></td><td>{}</td></tr>\n",
+                        Self::function_page_name(function_name),
+                        Self::overload_anchor(overload.function_full_name),
+                        Self::escape_html(overload.function_full_name),
+                        Self::escape_html(overload.description.lines().next().unwrap_or_default())
+                    ));
+                }
+            }
+            main.push_str("</table>\n");
+        }
+
+        let mut current_dir = "";
+        for object in &schema.objects {
+            if object.kind.dir() != current_dir {
+                if !current_dir.is_empty() {
+                    main.push_str("</table>\n");
+                }
+                current_dir = object.kind.dir();
+                main.push_str(&format!(
+                    "<h2>{}</h2>\n<table>\n<tr><th>Name</th><th>Type</th><th>Description</th></tr>\n",
+                    Self::dir_heading(current_dir)
+                ));
+            }
+            main.push_str(&format!(
+                "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td></tr>\n",
+                Self::object_page_name(object),
+                Self::escape_html(&object.name),
+                object.kind.label(),
+                Self::escape_html(
+                    object
+                        .description
+                        .as_deref()
+                        .and_then(|d| d.lines().next())
+                        .unwrap_or_default()
+                )
+            ));
+        }
+        if !current_dir.is_empty() {
+            main.push_str("</table>\n");
+        }
+
+        Self::render_page(
+            schema.schema,
+            "../",
+            &[String::from("search_index.js")],
+            &Self::render_schema_nav(schema, ""),
+            &main,
+        )
+    }
+
+    fn render_search_index(schema: &SchemaDocs) -> Result<String> {
+        let mut entries: Vec<SearchEntry> = schema
+            .functions
+            .iter()
+            .flat_map(|(function_name, overloads)| {
+                overloads.iter().map(move |overload| SearchEntry {
+                    schema: schema.schema,
+                    name: function_name,
+                    full_name: overload.function_full_name,
+                    description: overload.description,
+                    url: format!(
+                        "{}/{}#{}",
+                        Self::encode_url_component(schema.schema),
+                        Self::function_page_name(function_name),
+                        Self::overload_anchor(overload.function_full_name)
+                    ),
+                })
+            })
+            .collect();
+
+        entries.extend(schema.objects.iter().map(|object| SearchEntry {
+            schema: schema.schema,
+            name: &object.name,
+            full_name: &object.name,
+            description: object.description.as_deref().unwrap_or_default(),
+            url: format!(
+                "{}/{}",
+                Self::encode_url_component(schema.schema),
+                Self::object_page_name(object)
+            ),
+        }));
+
+        // Each schema appends to the same global so the root index can search every schema
+        Ok(format!(
+            "window.TUSK_SEARCH = (window.TUSK_SEARCH || []).concat({});\n",
+            serde_json::to_string(&entries)?
+        ))
+    }
+
+    // Write the index, search index and the pages of every function, table, view and data type
+    // for a single schema
+    pub async fn write_schema_docs(
+        schema: &str,
+        function_infos: &[FunctionDocParser<'_>],
+        objects: &[ObjectDoc],
+        call_graph: &CallGraph,
+    ) -> Result<()> {
+        let schema_docs =
+            SchemaDocs::new(schema, function_infos, objects).with_call_graph(call_graph);
+
+        let schema_dir = format!("{}/{}", HTML_DOC_ROOT, schema);
+        tokio::fs::create_dir_all(format!("{}/functions", schema_dir)).await?;
+
+        for function_name in schema_docs.functions.keys() {
+            tokio::fs::write(
+                format!("{}/{}", schema_dir, Self::function_page_name(function_name)),
+                Self::render_function_page(&schema_docs, function_name),
+            )
+            .await?;
+            println!("\t{} Docs Generated", function_name.bold().magenta());
+        }
+
+        for object in &schema_docs.objects {
+            let file_path = format!("{}/{}", schema_dir, Self::object_page_name(object));
+            tokio::fs::create_dir_all(format!("{}/{}", schema_dir, object.kind.dir())).await?;
+            tokio::fs::write(&file_path, Self::render_object_page(&schema_docs, object)).await?;
+            println!("\t{} Docs Generated", object.name.bold().magenta());
+        }
+
+        tokio::fs::write(
+            format!("{}/index.html", schema_dir),
+            Self::render_schema_index(&schema_docs),
+        )
+        .await?;
+        tokio::fs::write(
+            format!("{}/search_index.js", schema_dir),
+            Self::render_search_index(&schema_docs)?,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // Write the root index linking to every schema that has had html docs generated, including
+    // those generated by previous runs
+    pub async fn write_root_index() -> Result<()> {
+        let mut schemas = vec![];
+        for entry in walkdir::WalkDir::new(HTML_DOC_ROOT)
+            .min_depth(1)
+            .max_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_dir() && e.path().join("index.html").exists())
+        {
+            schemas.push(
+                entry
+                    .file_name()
+                    .to_str()
+                    .context("The file paths provided should be valid UTF-8 Characters")?
+                    .to_owned(),
+            );
+        }
+
+        let mut nav = String::from("<h3>Schemas</h3>\n<ul>\n");
+        for schema in &schemas {
+            nav.push_str(&format!(
+                "<li><a href=\"{}/index.html\">{}</a></li>\n",
+                Self::encode_url_component(schema),
+                Self::escape_html(schema)
+            ));
+        }
+        nav.push_str("</ul>");
+
+        let search_scripts: Vec<String> = schemas
+            .iter()
+            .map(|schema| format!("{}/search_index.js", Self::encode_url_component(schema)))
+            .collect();
+
+        let main = "<h1>Database Documentation</h1>\n<p>Select a schema to browse its functions, tables, views and data types, or use the search bar to find anything in any schema.</p>";
+
+        let file_path = format!("{}/index.html", HTML_DOC_ROOT);
+        tokio::fs::write(
+            &file_path,
+            Self::render_page("Database Documentation", "", &search_scripts, &nav, main),
+        )
+        .await?;
+        println!("\n{}: {}", "Generated".green(), file_path.magenta());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::doc::{ddl_doc_writer::ObjectKind, doc_parser::FunctionSee};
+
+    #[test]
+    fn overload_anchor_works() {
+        assert_eq!(
+            HtmlDocWriter::overload_anchor("concatenating(var1 text, var2 text)"),
+            "concatenating-var1-text-var2-text"
+        );
+        assert_eq!(HtmlDocWriter::overload_anchor("now()"), "now");
+        assert_ne!(
+            HtmlDocWriter::overload_anchor("f(a integer)"),
+            HtmlDocWriter::overload_anchor("f(a integer[])")
+        );
+        assert_eq!(
+            HtmlDocWriter::overload_anchor("f(a integer[])"),
+            "f-a-integer-array"
+        );
+        assert_eq!(
+            HtmlDocWriter::encode_url_component("\"MyFunc\""),
+            "%22MyFunc%22"
+        );
+    }
+
+    #[test]
+    fn render_function_page_works() {
+        let first = FunctionDocParser {
+            function_name: "concat",
+            function_full_name: "concat(var1 text, var2 text)",
+            schema: "public",
+            description: "Joins <two> things",
+            ..Default::default()
+        };
+        let second = FunctionDocParser {
+            function_name: "concat",
+            function_full_name: "concat(var1 text)",
+            schema: "public",
+            description: "Returns the input",
+            see: vec![FunctionSee {
+                target: "audit.log_change",
+                description: None,
+            }],
+            ..Default::default()
+        };
+        let other = FunctionDocParser {
+            function_name: "other",
+            function_full_name: "other()",
+            schema: "public",
+            ..Default::default()
+        };
+        let function_infos = [first, second, other];
+        let objects = [ObjectDoc {
+            schema: "public".to_string(),
+            name: "people".to_string(),
+            kind: ObjectKind::Table,
+            description: Some("Everyone we know".to_string()),
+            sections: vec![DocSection::Table {
+                heading: "Columns",
+                headers: vec!["Name", "Type"],
+                rows: vec![vec!["name".to_string(), "text".to_string()]],
+            }],
+        }];
+        let schema_docs = SchemaDocs::new("public", &function_infos, &objects);
+
+        let page = HtmlDocWriter::render_function_page(&schema_docs, "concat");
+
+        assert!(page.contains("<section class=\"overload\" id=\"concat-var1-text-var2-text\">"));
+        assert!(page.contains("<a href=\"#concat-var1-text\">concat(var1 text)</a>"));
+        assert!(page.contains("Joins &lt;two&gt; things"));
+        assert!(page.contains("<a href=\"../../audit/functions/log_change.html\">audit.log_change</a>"));
+        assert!(page.contains("<a href=\"../functions/other.html\">other</a>"));
+        assert!(page.contains("<a href=\"../tables/people.html\">people</a>"));
+        assert!(page.contains("<script src=\"../search_index.js\"></script>"));
+        assert!(
+            !page.contains("<link"),
+            "The page should not depend on external assets"
+        );
+
+        let page = HtmlDocWriter::render_object_page(&schema_docs, objects.first().unwrap());
+        assert!(page.contains("<tr><td>name</td><td>text</td></tr>"));
+        assert!(page.contains("<a href=\"../functions/concat.html\">concat</a>"));
+        assert!(page.contains("<script src=\"../search_index.js\"></script>"));
+
+        let search_index = HtmlDocWriter::render_search_index(&schema_docs).unwrap();
+        assert!(search_index.contains("\"url\":\"public/functions/concat.html#concat-var1-text\""));
+
+        // A function named index must not replace the index page of the schema
+        assert_eq!(HtmlDocWriter::function_page_name("index"), "functions/index.html");
+        assert!(search_index.contains("\"url\":\"public/tables/people.html\""));
+    }
+}
@@ -0,0 +1,307 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use super::{
+    ddl_doc_writer::ObjectDoc, doc_parser::FunctionDocParser, doc_writer::MARKDOWN_DOC_ROOT,
+};
+
+// A single documented (or undocumented) item listed within an index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub kind: &'static str,
+    pub signature: String,
+    pub description: String, // Only the first line
+    pub author: Option<String>,
+    pub date: Option<String>,
+    pub link: Option<String>, // Relative to the schemas documentation directory
+}
+
+// The table of contents of everything documented within a schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaIndex {
+    pub schema: String,
+    pub functions: Vec<IndexEntry>,
+    pub num_undocumented: usize,
+    pub objects: Vec<IndexEntry>,
+}
+
+// Get the anchor that markdown renderers such as GitHub generate for a heading
+pub fn markdown_anchor(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().trim().to_string()
+}
+
+impl SchemaIndex {
+    // Undocumented functions are given by their overload name (ie. the name of the file without
+    // .sql)
+    pub fn new(
+        schema: &str,
+        function_infos: &[FunctionDocParser<'_>],
+        undocumented_functions: &[&str],
+        objects: &[ObjectDoc],
+    ) -> Self {
+        let mut functions: Vec<IndexEntry> = function_infos
+            .iter()
+            .map(|function_info| IndexEntry {
+                kind: "Function",
+                signature: function_info.function_full_name.to_string(),
+                description: first_line(function_info.description),
+                author: function_info.author.map(str::to_string),
+                date: function_info.date.map(str::to_string),
+                link: Some(format!(
+                    "{}.md#{}",
+                    function_info.function_name,
+                    markdown_anchor(function_info.function_full_name)
+                )),
+            })
+            .collect();
+
+        functions.extend(
+            undocumented_functions
+                .iter()
+                .map(|overload_name| IndexEntry {
+                    kind: "Function",
+                    signature: overload_name.to_string(),
+                    description: String::from("**Warning: No doc comment**"),
+                    author: None,
+                    date: None,
+                    link: None,
+                }),
+        );
+        functions.sort_by(|a, b| a.signature.cmp(&b.signature));
+
+        let mut objects: Vec<IndexEntry> = objects
+            .iter()
+            .map(|object| IndexEntry {
+                kind: object.kind.label(),
+                signature: object.name.clone(),
+                description: object
+                    .description
+                    .as_deref()
+                    .map(first_line)
+                    .unwrap_or_default(),
+                author: None,
+                date: None,
+                link: Some(format!("{}/{}.md", object.kind.dir(), object.name)),
+            })
+            .collect();
+        objects.sort_by(|a, b| (a.kind, &a.signature).cmp(&(b.kind, &b.signature)));
+
+        Self {
+            schema: schema.to_string(),
+            functions,
+            num_undocumented: undocumented_functions.len(),
+            objects,
+        }
+    }
+
+    fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+        let clean = |cell: &str| cell.replace('|', "\\|");
+
+        let mut table = format!("| {} |\n", headers.join(" | "));
+        table.push_str(&format!(
+            "| {} |\n",
+            headers
+                .iter()
+                .map(|h| "-".repeat(h.len()))
+                .collect::<Vec<String>>()
+                .join(" | ")
+        ));
+        for row in rows {
+            table.push_str(&format!(
+                "| {} |\n",
+                row.iter()
+                    .map(|cell| clean(cell))
+                    .collect::<Vec<String>>()
+                    .join(" | ")
+            ));
+        }
+
+        table
+    }
+
+    fn link(entry: &IndexEntry, link_prefix: &str) -> String {
+        let signature = entry.signature.replace('|', "\\|");
+        match &entry.link {
+            Some(link) => format!("[{}](<{}{}>)", signature, link_prefix, link),
+            None => signature,
+        }
+    }
+
+    // Render the index with headings starting at the given level. The links are prefixed so that
+    // the index can be written somewhere other than the schemas documentation directory
+    pub fn to_markdown(&self, heading_level: usize, link_prefix: &str) -> String {
+        let heading = "#".repeat(heading_level);
+        let mut content = String::new();
+
+        if !self.functions.is_empty() {
+            content.push_str(&format!("{}# Functions\n\n", heading));
+            if self.num_undocumented > 0 {
+                content.push_str(&format!(
+                    "{} of {} functions have no doc comment.\n\n",
+                    self.num_undocumented,
+                    self.functions.len()
+                ));
+            }
+
+            let rows: Vec<Vec<String>> = self
+                .functions
+                .iter()
+                .map(|entry| {
+                    vec![
+                        Self::link(entry, link_prefix),
+                        entry.description.clone(),
+                        entry.author.clone().unwrap_or_default(),
+                        entry.date.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            content.push_str(&Self::markdown_table(
+                &["Signature", "Description", "Author", "Date"],
+                &rows,
+            ));
+            content.push('\n');
+        }
+
+        if !self.objects.is_empty() {
+            content.push_str(&format!("{}# Tables, Views and Data Types\n\n", heading));
+            let rows: Vec<Vec<String>> = self
+                .objects
+                .iter()
+                .map(|entry| {
+                    vec![
+                        Self::link(entry, link_prefix),
+                        entry.kind.to_string(),
+                        entry.description.clone(),
+                    ]
+                })
+                .collect();
+            content.push_str(&Self::markdown_table(
+                &["Name", "Type", "Description"],
+                &rows,
+            ));
+            content.push('\n');
+        }
+
+        if self.functions.is_empty() && self.objects.is_empty() {
+            content.push_str("Nothing has been documented in this schema.\n\n");
+        }
+
+        content
+    }
+}
+
+// Writes the table of contents of each schema and of the documentation as a whole
+pub struct IndexWriter {}
+
+impl IndexWriter {
+    // The call graph section is only written to the schema's README, not to the root index
+    pub async fn write_schema_index(index: &SchemaIndex, call_graph: &str) -> Result<()> {
+        let file_path = format!("{}/{}/README.md", MARKDOWN_DOC_ROOT, index.schema);
+        tokio::fs::create_dir_all(format!("{}/{}", MARKDOWN_DOC_ROOT, index.schema)).await?;
+        tokio::fs::write(
+            &file_path,
+            format!(
+                "# {}\n\n{}{}",
+                index.schema,
+                index.to_markdown(1, ""),
+                call_graph
+            ),
+        )
+        .await?;
+
+        println!("\t{} Generated", file_path.bold().magenta());
+        Ok(())
+    }
+
+    pub async fn write_root_index(indexes: &[SchemaIndex]) -> Result<()> {
+        let mut file_content = String::from("# Database Documentation\n\n");
+        for index in indexes {
+            file_content.push_str(&format!(
+                "## [{}](<{}/README.md>)\n\n",
+                index.schema, index.schema
+            ));
+            file_content.push_str(&index.to_markdown(2, &format!("{}/", index.schema)));
+        }
+
+        let file_path = format!("{}/README.md", MARKDOWN_DOC_ROOT);
+        tokio::fs::create_dir_all(MARKDOWN_DOC_ROOT).await?;
+        tokio::fs::write(&file_path, file_content).await?;
+
+        println!("\n{}: {}", "Generated".green(), file_path.magenta());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::doc::ddl_doc_writer::ObjectKind;
+
+    #[test]
+    fn markdown_anchor_works() {
+        assert_eq!(
+            markdown_anchor("concatenating(var1 text, var2 text)"),
+            "concatenatingvar1-text-var2-text"
+        );
+    }
+
+    #[test]
+    fn schema_index_works() {
+        let function_infos = [FunctionDocParser {
+            function_name: "concat",
+            function_full_name: "concat(var1 text, var2 text)",
+            schema: "public",
+            description: "Joins two things\nMore details",
+            author: Some("Homer Simpson"),
+            date: Some("01/02/1234"),
+            ..Default::default()
+        }];
+        let objects = [ObjectDoc {
+            schema: "public".to_string(),
+            name: "people".to_string(),
+            kind: ObjectKind::Table,
+            description: Some("Everyone we know".to_string()),
+            sections: vec![],
+        }];
+
+        let index = SchemaIndex::new(
+            "public",
+            &function_infos,
+            &["undocumented(integer)"],
+            &objects,
+        );
+
+        assert_eq!(
+            index.to_markdown(2, "public/"),
+            r#"### Functions
+
+1 of 2 functions have no doc comment.
+
+| Signature | Description | Author | Date |
+| --------- | ----------- | ------ | ---- |
+| [concat(var1 text, var2 text)](<public/concat.md#concatvar1-text-var2-text>) | Joins two things | Homer Simpson | 01/02/1234 |
+| undocumented(integer) | **Warning: No doc comment** |  |  |
+
+### Tables, Views and Data Types
+
+| Name | Type | Description |
+| ---- | ---- | ----------- |
+| [people](<public/tables/people.md>) | Table | Everyone we know |
+
+"#
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+
+use super::doc_parser::FunctionDocParser;
+
+pub const JSON_DOC_ROOT: &str = "./documentation/json";
+
+// Bump this whenever a field is removed or changes meaning. Adding fields doesn't require a bump
+pub const JSON_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ParamJson<'a> {
+    pub name: &'a str,
+    #[serde(rename = "type")]
+    pub param_type: &'a str,
+    pub description: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ReturnJson<'a> {
+    #[serde(rename = "type")]
+    pub return_type: &'a str,
+    pub description: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ThrowsJson<'a> {
+    pub sqlstate: &'a str,
+    pub description: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct SeeJson<'a> {
+    pub target: &'a str,
+    pub description: Option<&'a str>,
+}
+
+// The documentation of a single function overload. Every field is always present, using null or an
+// empty list when the tag wasn't given
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct FunctionJson<'a> {
+    pub schema: &'a str,
+    pub function_name: &'a str,
+    pub full_name: &'a str,
+    pub source_file: &'a str,
+    pub description: &'a str,
+    pub author: Option<&'a str>,
+    pub date: Option<&'a str>,
+    pub since: Option<&'a str>,
+    pub deprecated: Option<&'a str>,
+    pub params: Vec<ParamJson<'a>>,
+    pub returns: Option<ReturnJson<'a>>,
+    pub throws: Vec<ThrowsJson<'a>>,
+    pub examples: Vec<&'a str>,
+    pub see: Vec<SeeJson<'a>>,
+}
+
+impl<'a> FunctionJson<'a> {
+    pub fn new(function_info: &'a FunctionDocParser<'a>, source_file: &'a str) -> Self {
+        Self {
+            schema: function_info.schema,
+            function_name: function_info.function_name,
+            full_name: function_info.function_full_name,
+            source_file,
+            description: function_info.description,
+            author: function_info.author,
+            date: function_info.date,
+            since: function_info.since,
+            deprecated: function_info.deprecated,
+            params: function_info
+                .params
+                .iter()
+                .flatten()
+                .map(|param| ParamJson {
+                    name: param.name,
+                    param_type: param.param_type,
+                    description: param.description,
+                })
+                .collect(),
+            returns: function_info.returns.as_ref().map(|returns| ReturnJson {
+                return_type: returns.return_type,
+                description: returns.description,
+            }),
+            throws: function_info
+                .throws
+                .iter()
+                .map(|throws| ThrowsJson {
+                    sqlstate: throws.sqlstate,
+                    description: throws.description,
+                })
+                .collect(),
+            examples: function_info.examples.clone(),
+            see: function_info
+                .see
+                .iter()
+                .map(|see| SeeJson {
+                    target: see.target,
+                    description: see.description,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct SchemaJson<'a> {
+    pub format_version: u32,
+    pub schema: &'a str,
+    pub functions: Vec<FunctionJson<'a>>,
+}
+
+// Writes the documentation of each schema as a single json document
+pub struct JsonDocWriter {}
+
+impl JsonDocWriter {
+    pub fn to_json(schema: &str, functions: Vec<FunctionJson<'_>>) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&SchemaJson {
+            format_version: JSON_FORMAT_VERSION,
+            schema,
+            functions,
+        })?)
+    }
+
+    pub async fn write_schema_docs(schema: &str, functions: Vec<FunctionJson<'_>>) -> Result<()> {
+        let file_path = format!("{}/{}.json", JSON_DOC_ROOT, schema);
+        tokio::fs::create_dir_all(JSON_DOC_ROOT).await?;
+        tokio::fs::write(&file_path, Self::to_json(schema, functions)?).await?;
+
+        println!("\t{} Generated", file_path.bold().magenta());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::doc::doc_parser::FunctionReturn;
+
+    #[test]
+    fn to_json_works() {
+        let function_info = FunctionDocParser {
+            function_name: "concat",
+            function_full_name: "concat(var1 text)",
+            schema: "public",
+            description: "Returns the input",
+            returns: Some(FunctionReturn {
+                return_type: "TEXT",
+                description: None,
+            }),
+            examples: vec!["SELECT public.concat('a');"],
+            ..Default::default()
+        };
+
+        let json = JsonDocWriter::to_json(
+            "public",
+            vec![FunctionJson::new(
+                &function_info,
+                "./schemas/public/functions/concat/concat(text).sql",
+            )],
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!({
+                "format_version": 1,
+                "schema": "public",
+                "functions": [{
+                    "schema": "public",
+                    "function_name": "concat",
+                    "full_name": "concat(var1 text)",
+                    "source_file": "./schemas/public/functions/concat/concat(text).sql",
+                    "description": "Returns the input",
+                    "author": null,
+                    "date": null,
+                    "since": null,
+                    "deprecated": null,
+                    "params": [],
+                    "returns": { "type": "TEXT", "description": null },
+                    "throws": [],
+                    "examples": ["SELECT public.concat('a');"],
+                    "see": []
+                }]
+            })
+        );
+    }
+}
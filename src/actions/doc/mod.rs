@@ -1,17 +1,51 @@
+pub mod call_graph;
+pub mod ddl_doc_parser;
+pub mod ddl_doc_writer;
+pub mod doc_checker;
 pub mod doc_parser;
+pub mod doc_tester;
 pub mod doc_writer;
+pub mod html_writer;
+pub mod index_writer;
+pub mod json_writer;
 
-use std::path::Path;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
 
-use anyhow::{Context, Result};
-use clap::Args;
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
 use colored::Colorize;
+use sqlx::{Acquire, Postgres};
 
-use crate::config_file_manager::ddl_config::{
-    get_matching_file_contents, get_uncommented_file_contents,
+use crate::{
+    actions::{
+        function_signature::FunctionSignature,
+        unit_test::{
+            test_runner::{TestFilter, TestRunner},
+            TestStats,
+        },
+    },
+    config_file_manager::{
+        ddl_config::{get_matching_file_contents, get_uncommented_file_contents},
+        user_config::UserConfig,
+    },
+    db_manager,
 };
 
-use self::{doc_parser::FunctionDocParser, doc_writer::FunctionDocWriter};
+use self::{
+    call_graph::{CallGraph, FunctionRef},
+    ddl_doc_parser::{DataTypeDoc, RelationDoc},
+    ddl_doc_writer::{DdlDocWriter, ObjectDoc},
+    doc_checker::DocChecker,
+    doc_parser::{get_doc_comment_parser, FunctionDocParser},
+    doc_tester::DocTester,
+    doc_writer::{FunctionDocWriter, MARKDOWN_DOC_ROOT},
+    html_writer::{HtmlDocWriter, HTML_DOC_ROOT},
+    index_writer::{IndexWriter, SchemaIndex},
+    json_writer::{FunctionJson, JsonDocWriter, JSON_DOC_ROOT},
+};
 
 use super::init::SCHEMA_CONFIG_LOCATION;
 
@@ -21,15 +55,48 @@ struct FunctionFile {
     file_path: String,
 }
 
+// The contents of a function file along with the names needed to document it
+#[derive(Debug, Clone)]
+struct FunctionSource {
+    func_name: String,
+    overload_name: String,
+    file_path: String,
+    contents: String,
+    doc_comment: Option<String>, // With the comment syntax removed
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DocFormat {
+    /// One markdown file per function, table, view and data type in ./documentation/markdown/<schema>
+    Markdown,
+    /// A static html site with search in ./documentation/html
+    Html,
+    /// One json document per schema in ./documentation/json
+    Json,
+}
+
 #[derive(Debug, Args)]
 pub struct Doc {
-    /// Specify that you want to generate docs for functions in all schemas
-    #[arg(short, long, exclusive(true))]
+    /// Specify that you want to generate docs for all schemas
+    #[arg(short, long, conflicts_with("schemas"))]
     all: bool,
 
-    /// The schemas you want to generate function docs for
+    /// The schemas you want to generate docs for
     #[clap(num_args = 1.., index=1, required_unless_present="all")]
     schemas: Vec<String>,
+
+    /// The format of the generated documentation
+    #[arg(long, value_enum, default_value_t = DocFormat::Markdown)]
+    format: DocFormat,
+
+    /// Check the doc comments against the function signatures instead of generating docs
+    #[arg(long, conflicts_with("test"))]
+    check: bool,
+
+    /// Run the @example of each function as a unit test against the database instead of
+    /// generating docs. Everything is rolled back afterwards
+    #[arg(long)]
+    test: bool,
 }
 
 impl Doc {
@@ -80,38 +147,357 @@ impl Doc {
             }
         }
 
+        // Sort so that overloads are always documented in the same order
+        function_files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
         Ok(function_files)
     }
 
+    async fn read_function_sources(schema: &str) -> Result<Vec<FunctionSource>> {
+        // The doc comment style is optional so that docs can be generated without a user config
+        let comment_style = UserConfig::get_global()
+            .map(|config| config.doc_options.comment_style)
+            .unwrap_or_default();
+        let parser = get_doc_comment_parser(comment_style, schema).await?;
+
+        let mut sources = vec![];
+        for ff in Self::get_functions_from_schema(schema)? {
+            let overload_name = Path::new(&ff.file_path)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .context("The file paths provided should be valid UTF-8 Characters")?
+                .to_owned();
+
+            let contents = tokio::fs::read_to_string(&ff.file_path).await?;
+            let doc_comment = parser
+                .find_doc_comment(&overload_name, &contents)
+                .context(format!("Could not read the doc comment of {}", ff.file_path))?
+                .map(|doc_comment| doc_comment.into_owned());
+
+            sources.push(FunctionSource {
+                func_name: ff.func_name,
+                overload_name,
+                file_path: ff.file_path,
+                contents,
+                doc_comment,
+            });
+        }
+
+        Ok(sources)
+    }
+
+    // Parse the doc comments of the functions, also returning the overload names of those without
+    // one
+    fn parse_functions<'f>(
+        schema: &'f str,
+        sources: &'f [FunctionSource],
+    ) -> Result<(Vec<FunctionDocParser<'f>>, Vec<&'f str>)> {
+        let mut function_infos = vec![];
+        let mut undocumented = vec![];
+        for source in sources {
+            match &source.doc_comment {
+                Some(doc_comment) => function_infos.push(FunctionDocParser::from_doc_comment(
+                    schema,
+                    &source.func_name,
+                    &source.contents,
+                    doc_comment,
+                )?),
+                None => undocumented.push(source.overload_name.as_str()),
+            }
+        }
+
+        Ok((function_infos, undocumented))
+    }
+
+    // Build the call graph from the local definitions of the functions in every schema, so that
+    // calls from other schemas are included
+    async fn build_call_graph(schemas: &[String]) -> Result<CallGraph> {
+        let mut definitions = vec![];
+        for schema in schemas {
+            for ff in Self::get_functions_from_schema(schema)? {
+                definitions.push((
+                    FunctionRef {
+                        schema: schema.clone(),
+                        name: ff.func_name,
+                    },
+                    tokio::fs::read_to_string(&ff.file_path).await?,
+                ));
+            }
+        }
+
+        Ok(CallGraph::build(&definitions))
+    }
+
+    // Parse the pulled tables, views, materialized views and data types of the schema into docs
+    async fn get_objects_from_schema(schema: &str) -> Result<Vec<ObjectDoc>> {
+        let mut objects = vec![];
+        for ddl_dir in ["table_ddl", "views", "materialized_views", "data_types"] {
+            let dir_walker = walkdir::WalkDir::new(format!("./schemas/{}/{}", schema, ddl_dir))
+                .min_depth(1)
+                .max_depth(1)
+                .sort_by_file_name();
+            for entry in dir_walker
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|e| e.file_type().is_file())
+            {
+                let file_path = entry.path();
+                if file_path.extension().and_then(|ext| ext.to_str()) != Some("sql") {
+                    continue;
+                }
+
+                let name = file_path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .context("The file paths provided should be valid UTF-8 Characters")?;
+                let file_contents = tokio::fs::read_to_string(file_path).await?;
+
+                let object = if ddl_dir == "data_types" {
+                    DataTypeDoc::from_definition(schema, name, &file_contents)
+                        .map(|data_type| data_type.as_ref().map(ObjectDoc::from))
+                } else {
+                    RelationDoc::from_pg_dump(schema, name, &file_contents)
+                        .map(|relation| relation.as_ref().map(ObjectDoc::from))
+                }
+                .context(format!("Could not generate the docs for {:?}", file_path))?;
+
+                objects.extend(object);
+            }
+        }
+
+        Ok(objects)
+    }
+
+    async fn get_schema_index(schema: &str) -> Result<SchemaIndex> {
+        let sources = Self::read_function_sources(schema).await?;
+        let (function_infos, undocumented) = Self::parse_functions(schema, &sources)?;
+        let objects = Self::get_objects_from_schema(schema).await?;
+
+        Ok(SchemaIndex::new(schema, &function_infos, &undocumented, &objects))
+    }
+
+    // Report the doc comments which disagree with the signature of their function, returning the
+    // number of issues found
+    async fn check_schema_docs(schema: &str) -> Result<usize> {
+        let mut num_issues = 0;
+        for source in Self::read_function_sources(schema).await? {
+            let Some(doc_comment) = &source.doc_comment else {
+                continue;
+            };
+            let function_info = FunctionDocParser::from_doc_comment(
+                schema,
+                &source.func_name,
+                &source.contents,
+                doc_comment,
+            )?;
+            let signature =
+                FunctionSignature::from_definition(schema, &source.overload_name, &source.contents)
+                    .context(format!(
+                        "Could not parse the signature of {}.{}",
+                        schema, source.overload_name
+                    ))?;
+
+            let issues = DocChecker::check(&function_info, &signature);
+            if issues.is_empty() {
+                continue;
+            }
+
+            println!("\t{}:", source.overload_name.bold().magenta());
+            for issue in &issues {
+                println!("\t\t{}: {}", "Issue".red(), issue);
+            }
+            num_issues += issues.len();
+        }
+
+        Ok(num_issues)
+    }
+
+    async fn check(schemas: &[String]) -> Result<()> {
+        let mut num_issues = 0;
+        for schema in schemas {
+            println!("\nChecking {} schema docs:", schema);
+            num_issues += Self::check_schema_docs(schema).await?;
+        }
+
+        if num_issues > 0 {
+            bail!(
+                "{} doc comment issues were found, the docs no longer match the functions",
+                num_issues
+            );
+        }
+
+        println!(
+            "\n{}: The doc comments match the function signatures",
+            "Passed".green()
+        );
+        Ok(())
+    }
+
+    // Run the examples of every documented function in the schema, printing the results
+    async fn test_schema_docs<'c, C>(conn: C, schema: &str) -> Result<TestStats>
+    where
+        C: Acquire<'c, Database = Postgres>,
+    {
+        let mut conn = conn.acquire().await?;
+        let sources = Self::read_function_sources(schema).await?;
+        let (function_infos, _) = Self::parse_functions(schema, &sources)?;
+
+        let mut test_stats = TestStats::default();
+        // The function infos are in the same order as the documented sources
+        for (source, function_info) in sources
+            .iter()
+            .filter(|source| source.doc_comment.is_some())
+            .zip(&function_infos)
+        {
+            let tests = DocTester::get_test_configs(function_info);
+            if tests.is_empty() {
+                continue;
+            }
+
+            let test_results = TestRunner::new(tests)
+                .run_tests(&mut *conn, &TestFilter::default())
+                .await?;
+            for test_result in test_results {
+                test_stats.record(&source.file_path, test_result);
+            }
+        }
+
+        Ok(test_stats)
+    }
+
+    async fn test(schemas: &[String]) -> Result<()> {
+        let connection = db_manager::DbConnection::new().await?;
+        let mut transaction = connection.get_connection_pool().begin().await?;
+
+        let mut test_stats = TestStats::default();
+        for schema in schemas {
+            println!("\nTesting {} schema doc examples:", schema);
+            test_stats += Self::test_schema_docs(&mut *transaction, schema).await?;
+        }
+        // The examples are only run to check them, never to change the database
+        transaction.rollback().await?;
+
+        println!(
+            "\n{}: {}, {}: {}",
+            "Passed".green(),
+            test_stats.num_passed,
+            "Failed".red(),
+            test_stats.num_failed
+        );
+        if test_stats.num_failed > 0 {
+            bail!("{} doc examples failed", test_stats.num_failed);
+        }
+
+        Ok(())
+    }
+
     pub async fn execute(&self) -> Result<()> {
-        let mut schemas = get_uncommented_file_contents(SCHEMA_CONFIG_LOCATION)?;
+        let all_schemas = get_uncommented_file_contents(SCHEMA_CONFIG_LOCATION)?;
+        let mut schemas = all_schemas.clone();
 
         if !self.all {
             schemas = get_matching_file_contents(schemas.into_iter(), &self.schemas, None)?;
         }
 
+        if self.check {
+            return Self::check(&schemas).await;
+        }
+
+        if self.test {
+            return Self::test(&schemas).await;
+        }
+
+        let mut schema_indexes = BTreeMap::new();
+        let call_graph = Self::build_call_graph(&all_schemas).await?;
+
         for schema in &schemas {
             println!("\nBeginning {} schema doc generation:", schema);
 
-            let dir_path = format!("./documentation/{}",schema);
-            if std::path::Path::new(&dir_path).exists() {
+            let dir_path = match self.format {
+                DocFormat::Markdown => format!("{}/{}", MARKDOWN_DOC_ROOT, schema),
+                DocFormat::Html => format!("{}/{}", HTML_DOC_ROOT, schema),
+                DocFormat::Json => String::new(), // A single file that is overwritten
+            };
+            if !dir_path.is_empty() && std::path::Path::new(&dir_path).exists() {
                 // Only clean the directory if it exists already
                 std::fs::remove_dir_all(&dir_path)?;
                 println!("\t{}: Directory {}", "Cleaned".yellow(), dir_path.magenta());
             }
 
-            let function_files = Self::get_functions_from_schema(schema)?;
-            // This is where the multi threadedness will happen
-            for ff in function_files {
+            let sources = Self::read_function_sources(schema).await?;
+            let (function_infos, undocumented) = Self::parse_functions(schema, &sources)?;
+            let objects = Self::get_objects_from_schema(schema).await?;
+
+            for overload_name in &undocumented {
+                println!(
+                    "\t{}: {} has no doc comment",
+                    "Warning".yellow(),
+                    overload_name.magenta()
+                );
+            }
 
-                let file_contents = tokio::fs::read_to_string(ff.file_path).await?;
+            match self.format {
+                DocFormat::Markdown => {
+                    for function_info in &function_infos {
+                        FunctionDocWriter::write_doc_to_file(function_info).await?;
+                    }
+                    let function_names: BTreeSet<&str> = function_infos
+                        .iter()
+                        .map(|function_info| function_info.function_name)
+                        .collect();
+                    for function_name in function_names {
+                        let function = FunctionRef {
+                            schema: schema.clone(),
+                            name: function_name.to_string(),
+                        };
+                        FunctionDocWriter::write_call_graph_to_file(&function, &call_graph).await?;
+                    }
+                    for object in &objects {
+                        DdlDocWriter::write_doc_to_file(object).await?;
+                    }
 
-                let function_info = FunctionDocParser::new(schema, &ff.func_name, &file_contents)?;
+                    let index = SchemaIndex::new(schema, &function_infos, &undocumented, &objects);
+                    IndexWriter::write_schema_index(&index, &call_graph.to_markdown(schema))
+                        .await?;
+                    schema_indexes.insert(schema.clone(), index);
+                }
+                DocFormat::Html => {
+                    HtmlDocWriter::write_schema_docs(schema, &function_infos, &objects, &call_graph)
+                        .await?
+                }
+                DocFormat::Json => {
+                    // The function infos are in the same order as the documented sources
+                    let functions = sources
+                        .iter()
+                        .filter(|source| source.doc_comment.is_some())
+                        .zip(&function_infos)
+                        .map(|(source, function_info)| {
+                            FunctionJson::new(function_info, &source.file_path)
+                        })
+                        .collect();
+                    JsonDocWriter::write_schema_docs(schema, functions).await?
+                }
+            }
+        }
 
-                if let Some(function_info) = function_info {
-                    FunctionDocWriter::write_doc_to_file(&function_info).await?;
+        match self.format {
+            DocFormat::Markdown => {
+                // The root index covers every schema, not only those generated by this run
+                let mut indexes = vec![];
+                for schema in &all_schemas {
+                    indexes.push(match schema_indexes.remove(schema) {
+                        Some(index) => index,
+                        None => Self::get_schema_index(schema).await?,
+                    });
                 }
+                IndexWriter::write_root_index(&indexes).await?;
             }
+            DocFormat::Html => HtmlDocWriter::write_root_index().await?,
+            DocFormat::Json => println!(
+                "\n{}: The docs can be found in {}",
+                "Generated".green(),
+                JSON_DOC_ROOT.magenta()
+            ),
         }
 
         Ok(())
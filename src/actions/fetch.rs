@@ -7,7 +7,11 @@ use sqlx::postgres::PgRow;
 use sqlx::{PgPool, Row};
 
 use crate::config_file_manager::ddl_config::get_uncommented_file_contents;
-use crate::actions::init::SCHEMA_CONFIG_LOCATION;
+use crate::actions::init::{
+    DATABASE_SETTING_CONFIG_LOCATION, EXTENSION_CONFIG_LOCATION,
+    FOREIGN_DATA_WRAPPER_CONFIG_LOCATION, FOREIGN_SERVER_CONFIG_LOCATION, SCHEMA_CONFIG_LOCATION,
+    USER_MAPPING_CONFIG_LOCATION,
+};
 
 use crate::config_file_manager::user_config::UserConfig;
 use crate::{config_file_manager, db_manager};
@@ -94,6 +98,163 @@ impl Fetch {
         Ok(SchemaListStatus::AlreadyLoaded)
     }
 
+    async fn fetch_extensions_list(&self, pool: &PgPool) -> Result<()> {
+        // Create the file that will contain the extension config if it does not already exist
+        if !std::path::Path::new(EXTENSION_CONFIG_LOCATION).exists() {
+            std::fs::write(EXTENSION_CONFIG_LOCATION, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before extensions were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("extensions").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        self.fetch_list(
+            pool,
+            "
+                SELECT extname AS item_name
+                FROM pg_catalog.pg_extension
+                ORDER BY item_name
+            ",
+            EXTENSION_CONFIG_LOCATION,
+            "\tExtensions",
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_database_settings_list(&self, pool: &PgPool) -> Result<()> {
+        // Create the file that will contain the database setting config if it does not already exist
+        if !std::path::Path::new(DATABASE_SETTING_CONFIG_LOCATION).exists() {
+            std::fs::write(DATABASE_SETTING_CONFIG_LOCATION, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before database settings were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("database_settings").unwrap_or(&true);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // Only the settings made with ALTER DATABASE ... SET for the database itself, not per role
+        self.fetch_list(
+            pool,
+            "
+                SELECT split_part(setting, '=', 1) AS item_name
+                FROM pg_catalog.pg_db_role_setting s
+                CROSS JOIN UNNEST(s.setconfig) setting
+                WHERE s.setdatabase = (SELECT oid FROM pg_catalog.pg_database WHERE datname = current_database())
+                AND s.setrole = 0
+                ORDER BY item_name
+            ",
+            DATABASE_SETTING_CONFIG_LOCATION,
+            "\tDatabase settings",
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_foreign_data_wrappers_list(&self, pool: &PgPool) -> Result<()> {
+        // Create the file that will contain the foreign data wrapper config if it does not already exist
+        if !std::path::Path::new(FOREIGN_DATA_WRAPPER_CONFIG_LOCATION).exists() {
+            std::fs::write(FOREIGN_DATA_WRAPPER_CONFIG_LOCATION, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before foreign data wrappers were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("foreign_data_wrappers").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // Foreign data wrappers created by an extension are created with the extension
+        self.fetch_list(
+            pool,
+            "
+                SELECT w.fdwname AS item_name
+                FROM pg_catalog.pg_foreign_data_wrapper w
+                WHERE NOT EXISTS (
+                    SELECT 1
+                    FROM pg_catalog.pg_depend d
+                    WHERE d.classid = 'pg_foreign_data_wrapper'::regclass
+                    AND d.objid = w.oid
+                    AND d.deptype = 'e'
+                )
+                ORDER BY item_name
+            ",
+            FOREIGN_DATA_WRAPPER_CONFIG_LOCATION,
+            "\tForeign data wrappers",
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_foreign_servers_list(&self, pool: &PgPool) -> Result<()> {
+        // Create the file that will contain the foreign server config if it does not already exist
+        if !std::path::Path::new(FOREIGN_SERVER_CONFIG_LOCATION).exists() {
+            std::fs::write(FOREIGN_SERVER_CONFIG_LOCATION, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before foreign servers were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("foreign_servers").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        self.fetch_list(
+            pool,
+            "
+                SELECT srvname AS item_name
+                FROM pg_catalog.pg_foreign_server
+                ORDER BY item_name
+            ",
+            FOREIGN_SERVER_CONFIG_LOCATION,
+            "\tForeign servers",
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_user_mappings_list(&self, pool: &PgPool) -> Result<()> {
+        // Create the file that will contain the user mapping config if it does not already exist
+        if !std::path::Path::new(USER_MAPPING_CONFIG_LOCATION).exists() {
+            std::fs::write(USER_MAPPING_CONFIG_LOCATION, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before user mappings were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("user_mappings").unwrap_or(&true);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // User mappings are listed as user@server with PUBLIC as the user of mappings for all roles
+        self.fetch_list(
+            pool,
+            "
+                SELECT format(
+                    '%s@%s',
+                    CASE WHEN umuser = 0 THEN 'PUBLIC' ELSE usename END,
+                    srvname
+                ) AS item_name
+                FROM pg_catalog.pg_user_mappings
+                ORDER BY item_name
+            ",
+            USER_MAPPING_CONFIG_LOCATION,
+            "\tUser mappings",
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
     async fn fetch_function_lists(&self, pool: &PgPool, schema: &str) -> Result<()> {
         let mut config_path = format!("./.tusk/config/schemas/{}", schema);
         std::fs::create_dir_all(&config_path)
@@ -146,16 +307,26 @@ impl Fetch {
         let new_items_commented = config.fetch_options.new_items_commented.get("table_ddl").context("new_items_commented must contain a field table_ddl")?;
         let delete_items_from_config = config.fetch_options.delete_items_from_config;
 
+        // Partitions are part of their partitioned table unless they are asked for individually
         self.fetch_list(
             pool,
             &format!(
                 "
                         SELECT table_name AS item_name
-                        FROM information_schema.tables
+                        FROM information_schema.tables t
                         WHERE table_schema = '{}'
-                        AND table_type ILIKE '%TABLE%';
+                        AND table_type ILIKE '%TABLE%'
+                        AND ({} OR NOT EXISTS (
+                            SELECT 1
+                            FROM pg_catalog.pg_class c
+                            JOIN pg_catalog.pg_namespace ns ON ns.oid = c.relnamespace
+                            WHERE ns.nspname = t.table_schema
+                            AND c.relname = t.table_name
+                            AND c.relispartition
+                        ));
                     ",
-                schema
+                schema,
+                config.fetch_options.include_partitions
             ),
             &config_path,
             &format!("\t{}: Table DDL", schema.magenta()),
@@ -264,7 +435,7 @@ impl Fetch {
                     FROM pg_class c
                     JOIN pg_catalog.pg_namespace ns ON ns.oid = c.relnamespace 
                     WHERE ns.nspname = '{}'
-                    AND relkind IN ('m', 'v')
+                    AND relkind = 'v'
                     ORDER BY item_name
                     ",
                 schema
@@ -279,6 +450,448 @@ impl Fetch {
         Ok(())
     }
 
+    async fn fetch_materialized_views_list(&self, pool: &PgPool, schema: &str) -> Result<()> {
+        let mut config_path = format!("./.tusk/config/schemas/{}", schema);
+        std::fs::create_dir_all(&config_path)
+            .expect("Should be able to create the required directories");
+        config_path += "/materialized_views_to_include.conf";
+
+        // Create the file that will contain the materialized view config if it does not already exist
+        if !std::path::Path::new(&config_path).exists() {
+            std::fs::write(&config_path, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before materialized views were split from views won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("materialized_views").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        self.fetch_list(
+            pool,
+            &format!(
+                "
+                    SELECT c.relname AS item_name
+                    FROM pg_class c
+                    JOIN pg_catalog.pg_namespace ns ON ns.oid = c.relnamespace
+                    WHERE ns.nspname = '{}'
+                    AND relkind = 'm'
+                    ORDER BY item_name
+                    ",
+                schema
+            ),
+            &config_path,
+            &format!("\t{}: Materialized views", schema.magenta()),
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_triggers_list(&self, pool: &PgPool, schema: &str) -> Result<()> {
+        let mut config_path = format!("./.tusk/config/schemas/{}", schema);
+        std::fs::create_dir_all(&config_path)
+            .expect("Should be able to create the required directories");
+        config_path += "/triggers_to_include.conf";
+
+        // Create the file that will contain the trigger config if it does not already exist
+        if !std::path::Path::new(&config_path).exists() {
+            std::fs::write(&config_path, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before triggers were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("triggers").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // Triggers cloned onto partitions from their partitioned table are part of that table's
+        // trigger unless partitions are asked for individually. A cloned trigger has the same name as
+        // a trigger on the parent, which unlike tgparentid also works before Postgres 13
+        self.fetch_list(
+            pool,
+            &format!(
+                "
+                    SELECT format('%s.%s', c.relname, t.tgname) AS item_name
+                    FROM pg_trigger t
+                    JOIN pg_class c ON c.oid = t.tgrelid
+                    JOIN pg_catalog.pg_namespace ns ON ns.oid = c.relnamespace
+                    WHERE ns.nspname = '{}'
+                    AND NOT t.tgisinternal
+                    AND ({} OR NOT (
+                        c.relispartition
+                        AND EXISTS (
+                            SELECT 1
+                            FROM pg_inherits inh
+                            JOIN pg_trigger pt ON pt.tgrelid = inh.inhparent AND pt.tgname = t.tgname
+                            WHERE inh.inhrelid = c.oid
+                        )
+                    ))
+                    ORDER BY item_name
+                    ",
+                schema,
+                config.fetch_options.include_partitions
+            ),
+            &config_path,
+            &format!("\t{}: Triggers", schema.magenta()),
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_sequences_list(&self, pool: &PgPool, schema: &str) -> Result<()> {
+        let mut config_path = format!("./.tusk/config/schemas/{}", schema);
+        std::fs::create_dir_all(&config_path)
+            .expect("Should be able to create the required directories");
+        config_path += "/sequences_to_include.conf";
+
+        // Create the file that will contain the sequence config if it does not already exist
+        if !std::path::Path::new(&config_path).exists() {
+            std::fs::write(&config_path, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before sequences were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("sequences").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // Identity sequences are part of their table so they are left to the table ddl
+        self.fetch_list(
+            pool,
+            &format!(
+                "
+                    SELECT c.relname AS item_name
+                    FROM pg_class c
+                    JOIN pg_catalog.pg_namespace ns ON ns.oid = c.relnamespace
+                    WHERE ns.nspname = '{}'
+                    AND c.relkind = 'S'
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM pg_depend d
+                        WHERE d.classid = 'pg_class'::regclass
+                        AND d.objid = c.oid
+                        AND d.deptype = 'i'
+                    )
+                    ORDER BY item_name
+                    ",
+                schema
+            ),
+            &config_path,
+            &format!("\t{}: Sequences", schema.magenta()),
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_indexes_list(&self, pool: &PgPool, schema: &str) -> Result<()> {
+        let mut config_path = format!("./.tusk/config/schemas/{}", schema);
+        std::fs::create_dir_all(&config_path)
+            .expect("Should be able to create the required directories");
+        config_path += "/indexes_to_include.conf";
+
+        // Create the file that will contain the index config if it does not already exist
+        if !std::path::Path::new(&config_path).exists() {
+            std::fs::write(&config_path, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before indexes were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("indexes").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // Indexes backing primary key, unique and exclusion constraints are left to the table ddl and
+        // the indexes of materialized views are pulled with the materialized view. Indexes attached
+        // to the index of a partitioned table are part of that index unless partitions are asked for
+        // individually
+        self.fetch_list(
+            pool,
+            &format!(
+                "
+                    SELECT i.relname AS item_name
+                    FROM pg_index x
+                    JOIN pg_class i ON i.oid = x.indexrelid
+                    JOIN pg_class t ON t.oid = x.indrelid
+                    JOIN pg_catalog.pg_namespace ns ON ns.oid = i.relnamespace
+                    WHERE ns.nspname = '{}'
+                    AND t.relkind <> 'm'
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM pg_constraint con
+                        WHERE con.conindid = x.indexrelid
+                        AND con.contype IN ('p', 'u', 'x')
+                    )
+                    AND ({} OR NOT EXISTS (
+                        SELECT 1
+                        FROM pg_inherits inh
+                        WHERE inh.inhrelid = x.indexrelid
+                    ))
+                    ORDER BY item_name
+                    ",
+                schema,
+                config.fetch_options.include_partitions
+            ),
+            &config_path,
+            &format!("\t{}: Indexes", schema.magenta()),
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_aggregates_list(&self, pool: &PgPool, schema: &str) -> Result<()> {
+        let mut config_path = format!("./.tusk/config/schemas/{}", schema);
+        std::fs::create_dir_all(&config_path)
+            .expect("Should be able to create the required directories");
+        config_path += "/aggregates_to_include.conf";
+
+        // Create the file that will contain the aggregate config if it does not already exist
+        if !std::path::Path::new(&config_path).exists() {
+            std::fs::write(&config_path, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before aggregates were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("aggregates").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // Aggregates from extensions are left out
+
+        self.fetch_list(
+            pool,
+            &format!(
+                "
+                    SELECT DISTINCT p.proname AS item_name
+                    FROM pg_proc p
+                    JOIN pg_catalog.pg_namespace ns ON ns.oid = p.pronamespace
+                    WHERE ns.nspname = '{}'
+                    AND p.prokind = 'a'
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM pg_depend d
+                        WHERE d.classid = 'pg_proc'::regclass
+                        AND d.objid = p.oid
+                        AND d.deptype = 'e'
+                    )
+                    ORDER BY item_name
+                    ",
+                schema
+            ),
+            &config_path,
+            &format!("\t{}: Aggregates", schema.magenta()),
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_operators_list(&self, pool: &PgPool, schema: &str) -> Result<()> {
+        let mut config_path = format!("./.tusk/config/schemas/{}", schema);
+        std::fs::create_dir_all(&config_path)
+            .expect("Should be able to create the required directories");
+        config_path += "/operators_to_include.conf";
+
+        // Create the file that will contain the operator config if it does not already exist
+        if !std::path::Path::new(&config_path).exists() {
+            std::fs::write(&config_path, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before operators were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("operators").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // Operators are listed as name(left_type,right_type) with the characters that can't be used
+        // in file names or config files percent encoded. Operators from extensions are left out
+        self.fetch_list(
+            pool,
+            &format!(
+                "
+                    SELECT format(
+                        '%s(%s,%s)',
+                        replace(replace(replace(replace(o.oprname, '%', '%25'), '/', '%2F'), '#', '%23'), '!', '%21'),
+                        COALESCE(lt.typname, 'NONE'),
+                        rt.typname
+                    ) AS item_name
+                    FROM pg_operator o
+                    JOIN pg_catalog.pg_namespace ns ON ns.oid = o.oprnamespace
+                    LEFT JOIN pg_type lt ON lt.oid = o.oprleft
+                    JOIN pg_type rt ON rt.oid = o.oprright
+                    WHERE ns.nspname = '{}'
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM pg_depend d
+                        WHERE d.classid = 'pg_operator'::regclass
+                        AND d.objid = o.oid
+                        AND d.deptype = 'e'
+                    )
+                    ORDER BY item_name
+                    ",
+                schema
+            ),
+            &config_path,
+            &format!("\t{}: Operators", schema.magenta()),
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_casts_list(&self, pool: &PgPool, schema: &str) -> Result<()> {
+        let mut config_path = format!("./.tusk/config/schemas/{}", schema);
+        std::fs::create_dir_all(&config_path)
+            .expect("Should be able to create the required directories");
+        config_path += "/casts_to_include.conf";
+
+        // Create the file that will contain the cast config if it does not already exist
+        if !std::path::Path::new(&config_path).exists() {
+            std::fs::write(&config_path, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before casts were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("casts").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // Casts don't belong to a schema so user defined casts are listed in the schema of their
+        // source type, then their target type, then their function. Casts from extensions are left out
+        self.fetch_list(
+            pool,
+            &format!(
+                "
+                    SELECT format('%s_to_%s', st.typname, tt.typname) AS item_name
+                    FROM pg_cast c
+                    JOIN pg_type st ON st.oid = c.castsource
+                    JOIN pg_catalog.pg_namespace sns ON sns.oid = st.typnamespace
+                    JOIN pg_type tt ON tt.oid = c.casttarget
+                    JOIN pg_catalog.pg_namespace tns ON tns.oid = tt.typnamespace
+                    LEFT JOIN pg_proc f ON f.oid = c.castfunc
+                    LEFT JOIN pg_catalog.pg_namespace fns ON fns.oid = f.pronamespace
+                    WHERE c.oid >= 16384
+                    AND CASE
+                        WHEN sns.nspname <> 'pg_catalog' THEN sns.nspname
+                        WHEN tns.nspname <> 'pg_catalog' THEN tns.nspname
+                        ELSE fns.nspname
+                    END = '{}'
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM pg_depend d
+                        WHERE d.classid = 'pg_cast'::regclass
+                        AND d.objid = c.oid
+                        AND d.deptype = 'e'
+                    )
+                    ORDER BY item_name
+                    ",
+                schema
+            ),
+            &config_path,
+            &format!("\t{}: Casts", schema.magenta()),
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_event_triggers_list(&self, pool: &PgPool, schema: &str) -> Result<()> {
+        let mut config_path = format!("./.tusk/config/schemas/{}", schema);
+        std::fs::create_dir_all(&config_path)
+            .expect("Should be able to create the required directories");
+        config_path += "/event_triggers_to_include.conf";
+
+        // Create the file that will contain the event trigger config if it does not already exist
+        if !std::path::Path::new(&config_path).exists() {
+            std::fs::write(&config_path, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before event triggers were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("event_triggers").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        // Event triggers don't belong to a schema so they are listed in the schema of their function.
+        // Event triggers from extensions are left out
+        self.fetch_list(
+            pool,
+            &format!(
+                "
+                    SELECT e.evtname AS item_name
+                    FROM pg_event_trigger e
+                    JOIN pg_proc f ON f.oid = e.evtfoid
+                    JOIN pg_catalog.pg_namespace ns ON ns.oid = f.pronamespace
+                    WHERE ns.nspname = '{}'
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM pg_depend d
+                        WHERE d.classid = 'pg_event_trigger'::regclass
+                        AND d.objid = e.oid
+                        AND d.deptype = 'e'
+                    )
+                    ORDER BY item_name
+                    ",
+                schema
+            ),
+            &config_path,
+            &format!("\t{}: Event triggers", schema.magenta()),
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_foreign_tables_list(&self, pool: &PgPool, schema: &str) -> Result<()> {
+        let mut config_path = format!("./.tusk/config/schemas/{}", schema);
+        std::fs::create_dir_all(&config_path)
+            .expect("Should be able to create the required directories");
+        config_path += "/foreign_tables_to_include.conf";
+
+        // Create the file that will contain the foreign table config if it does not already exist
+        if !std::path::Path::new(&config_path).exists() {
+            std::fs::write(&config_path, "")?;
+        }
+
+        let config = UserConfig::get_global()?;
+        // Configs created before foreign tables were tracked won't contain this field
+        let new_items_commented = config.fetch_options.new_items_commented.get("foreign_tables").unwrap_or(&false);
+        let delete_items_from_config = config.fetch_options.delete_items_from_config;
+
+        self.fetch_list(
+            pool,
+            &format!(
+                "
+                    SELECT c.relname AS item_name
+                    FROM pg_class c
+                    JOIN pg_catalog.pg_namespace ns ON ns.oid = c.relnamespace
+                    WHERE ns.nspname = '{}'
+                    AND c.relkind = 'f'
+                    ORDER BY item_name
+                    ",
+                schema
+            ),
+            &config_path,
+            &format!("\t{}: Foreign tables", schema.magenta()),
+            *new_items_commented,
+            delete_items_from_config
+        )
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn execute(&self) -> Result<()> {
         println!("\nBeginning Inventory Fetch:");
 
@@ -291,6 +904,13 @@ impl Fetch {
             return Ok(());
         }
 
+        println!("\nBeginning database fetch:");
+        self.fetch_extensions_list(pool).await?;
+        self.fetch_database_settings_list(pool).await?;
+        self.fetch_foreign_data_wrappers_list(pool).await?;
+        self.fetch_foreign_servers_list(pool).await?;
+        self.fetch_user_mappings_list(pool).await?;
+
         let approved_schemas = get_uncommented_file_contents(SCHEMA_CONFIG_LOCATION)?;
 
         for schema in approved_schemas {
@@ -300,6 +920,15 @@ impl Fetch {
             self.fetch_table_data_list(pool, &schema).await?;
             self.fetch_data_types_list(pool, &schema).await?;
             self.fetch_views_list(pool, &schema).await?;
+            self.fetch_materialized_views_list(pool, &schema).await?;
+            self.fetch_triggers_list(pool, &schema).await?;
+            self.fetch_sequences_list(pool, &schema).await?;
+            self.fetch_indexes_list(pool, &schema).await?;
+            self.fetch_aggregates_list(pool, &schema).await?;
+            self.fetch_operators_list(pool, &schema).await?;
+            self.fetch_casts_list(pool, &schema).await?;
+            self.fetch_event_triggers_list(pool, &schema).await?;
+            self.fetch_foreign_tables_list(pool, &schema).await?;
             println!();
         }
 
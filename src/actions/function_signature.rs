@@ -0,0 +1,543 @@
+use anyhow::{bail, Context, Result};
+use sqlx::{postgres::PgRow, PgPool, Row};
+
+// Types that are made up of more than one word. These are needed so that an unnamed argument such
+// as "double precision" is not mistaken for an argument named double of type precision
+const MULTI_WORD_TYPES: [&str; 9] = [
+    "double precision",
+    "character varying",
+    "bit varying",
+    "national character",
+    "timestamp with",
+    "timestamp without",
+    "time with",
+    "time without",
+    "interval ",
+];
+
+const FUNCTION_SIGNATURE_QUERY: &str = "
+        SELECT
+            format('%I(%s)', p.proname, oidvectortypes(p.proargtypes)) AS overload_name,
+            p.prokind = 'p' AS is_procedure,
+            p.proretset AS returns_set,
+            format_type(p.prorettype, NULL) AS return_type,
+            COALESCE(p.proargnames, ARRAY[]::TEXT[]) AS arg_names,
+            COALESCE(p.proargmodes::TEXT[], ARRAY[]::TEXT[]) AS arg_modes,
+            ARRAY(
+                SELECT format_type(arg.type_oid, NULL)
+                FROM UNNEST(COALESCE(p.proallargtypes, p.proargtypes::OID[])) WITH ORDINALITY AS arg(type_oid, position)
+                ORDER BY arg.position
+            ) AS arg_types,
+            p.pronargdefaults::INTEGER AS num_defaults,
+            ARRAY(
+                SELECT a.attname::TEXT
+                FROM pg_attribute a
+                WHERE a.attrelid = rt.typrelid AND a.attnum > 0 AND NOT a.attisdropped
+                ORDER BY a.attnum
+            ) AS composite_columns
+        FROM pg_proc p
+        JOIN pg_namespace ns ON ns.oid = p.pronamespace
+        JOIN pg_type rt ON rt.oid = p.prorettype
+        WHERE ns.nspname = $1
+        AND p.proname = $2
+        AND p.prokind IN ('f', 'p')
+        ORDER BY overload_name
+            ";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgMode {
+    In,
+    Out,
+    InOut,
+    Variadic,
+    Table,
+}
+
+impl ArgMode {
+    // Input arguments are the ones that need to be passed in when calling the function
+    pub fn is_input(&self) -> bool {
+        matches!(self, Self::In | Self::InOut | Self::Variadic)
+    }
+
+    // Output arguments become the columns of the result of the function
+    pub fn is_output(&self) -> bool {
+        matches!(self, Self::Out | Self::InOut | Self::Table)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionArg {
+    pub name: Option<String>,
+    pub arg_type: String,
+    pub mode: ArgMode,
+    pub has_default: bool,
+}
+
+// The signature of a single overload of a function or procedure
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionSignature {
+    pub schema: String,
+    pub name: String,
+    pub overload_name: String,
+    pub is_procedure: bool,
+    pub args: Vec<FunctionArg>,
+    pub return_type: Option<String>, // None for procedures
+    pub returns_set: bool,
+    pub composite_columns: Vec<String>, // The columns of the return type if it is a composite type
+}
+
+impl FunctionSignature {
+    pub fn input_args(&self) -> impl Iterator<Item = &FunctionArg> {
+        self.args.iter().filter(|arg| arg.mode.is_input())
+    }
+
+    // Get the names of the columns that will be returned when calling the function
+    pub fn output_columns(&self) -> Vec<String> {
+        let out_args: Vec<String> = self
+            .args
+            .iter()
+            .filter(|arg| arg.mode.is_output())
+            .enumerate()
+            .map(|(idx, arg)| {
+                arg.name
+                    .clone()
+                    .unwrap_or_else(|| format!("column{}", idx + 1))
+            })
+            .collect();
+
+        if !out_args.is_empty() {
+            return out_args;
+        }
+
+        if !self.composite_columns.is_empty() {
+            return self.composite_columns.clone();
+        }
+
+        match &self.return_type {
+            Some(return_type) if !return_type.eq_ignore_ascii_case("void") => {
+                vec![self.name.clone()]
+            }
+            _ => vec![],
+        }
+    }
+
+    // Parses the signature out of a CREATE FUNCTION or CREATE PROCEDURE statement such as those
+    // created by pg_get_functiondef
+    pub fn from_definition(schema: &str, overload_name: &str, definition: &str) -> Result<Self> {
+        let upper_definition = definition.to_uppercase();
+
+        let (keyword_pos, is_procedure) = match (
+            upper_definition.find("FUNCTION"),
+            upper_definition.find("PROCEDURE"),
+        ) {
+            (Some(f), Some(p)) if p < f => (p + "PROCEDURE".len(), true),
+            (Some(f), _) => (f + "FUNCTION".len(), false),
+            (None, Some(p)) => (p + "PROCEDURE".len(), true),
+            (None, None) => bail!("No CREATE FUNCTION or CREATE PROCEDURE statement was found"),
+        };
+
+        let args_start = keyword_pos
+            + definition[keyword_pos..]
+                .find('(')
+                .context("There was no opening bracket found within the function declaration")?;
+        let args_end = find_closing_bracket(definition, args_start)
+            .context("There was no closing bracket found within the function declaration")?;
+
+        let qualified_name = definition[keyword_pos..args_start].trim();
+        let name = unquote_ident(
+            split_top_level(qualified_name, '.')
+                .last()
+                .context("The function declaration should contain a name")?,
+        );
+
+        let args = split_top_level(&definition[args_start + 1..args_end], ',')
+            .into_iter()
+            .filter(|arg| !arg.trim().is_empty())
+            .map(|arg| parse_arg(arg, ArgMode::In))
+            .collect::<Result<Vec<FunctionArg>>>()?;
+
+        let mut signature = Self {
+            schema: schema.to_string(),
+            name,
+            overload_name: overload_name.to_string(),
+            is_procedure,
+            args,
+            return_type: None,
+            returns_set: false,
+            composite_columns: vec![],
+        };
+
+        if is_procedure {
+            return Ok(signature);
+        }
+
+        let returns_pos = match upper_definition[args_end..].find("RETURNS") {
+            Some(pos) => args_end + pos + "RETURNS".len(),
+            None => {
+                // Functions with OUT arguments don't need a RETURNS clause
+                signature.return_type = Some(String::from("record"));
+                return Ok(signature);
+            }
+        };
+
+        let returns_clause = definition[returns_pos..].trim_start();
+        if returns_clause.to_uppercase().starts_with("TABLE") {
+            let table_start = returns_clause
+                .find('(')
+                .context("RETURNS TABLE should be followed by a column list")?;
+            let table_end = find_closing_bracket(returns_clause, table_start)
+                .context("There was no closing bracket found for the RETURNS TABLE column list")?;
+
+            for column in split_top_level(&returns_clause[table_start + 1..table_end], ',') {
+                signature.args.push(parse_arg(column, ArgMode::Table)?);
+            }
+            signature.return_type = Some(String::from("record"));
+            signature.returns_set = true;
+            return Ok(signature);
+        }
+
+        let mut return_type = returns_clause
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        for end_keyword in [" LANGUAGE ", " AS ", " IMMUTABLE", " STABLE", " VOLATILE"] {
+            if let Some(pos) = return_type.to_uppercase().find(end_keyword) {
+                return_type.truncate(pos);
+            }
+        }
+
+        if return_type.to_uppercase().starts_with("SETOF ") {
+            signature.returns_set = true;
+            return_type = return_type["SETOF ".len()..].trim().to_string();
+        }
+        signature.return_type = Some(return_type);
+
+        Ok(signature)
+    }
+
+    // Gets the signature of every overload of the function from the database catalog
+    pub async fn from_catalog(pool: &PgPool, schema: &str, function_name: &str) -> Result<Vec<Self>> {
+        let rows = sqlx::query(FUNCTION_SIGNATURE_QUERY)
+            .bind(schema)
+            .bind(function_name)
+            .fetch_all(pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|row| Self::from_catalog_row(schema, function_name, row))
+            .collect()
+    }
+
+    fn from_catalog_row(schema: &str, function_name: &str, row: PgRow) -> Result<Self> {
+        let is_procedure: bool = row.try_get("is_procedure")?;
+        let arg_names: Vec<String> = row.try_get("arg_names")?;
+        let arg_modes: Vec<String> = row.try_get("arg_modes")?;
+        let arg_types: Vec<String> = row.try_get("arg_types")?;
+        let num_defaults: i32 = row.try_get("num_defaults")?;
+
+        let mut args: Vec<FunctionArg> = arg_types
+            .into_iter()
+            .enumerate()
+            .map(|(idx, arg_type)| {
+                let mode = match arg_modes.get(idx).map(String::as_str) {
+                    Some("o") => ArgMode::Out,
+                    Some("b") => ArgMode::InOut,
+                    Some("v") => ArgMode::Variadic,
+                    Some("t") => ArgMode::Table,
+                    _ => ArgMode::In,
+                };
+                let name = arg_names
+                    .get(idx)
+                    .filter(|name| !name.is_empty())
+                    .cloned();
+
+                FunctionArg {
+                    name,
+                    arg_type,
+                    mode,
+                    has_default: false,
+                }
+            })
+            .collect();
+
+        // Defaults always belong to the last input arguments
+        args.iter_mut()
+            .filter(|arg| arg.mode.is_input())
+            .rev()
+            .take(num_defaults.max(0) as usize)
+            .for_each(|arg| arg.has_default = true);
+
+        Ok(Self {
+            schema: schema.to_string(),
+            name: function_name.to_string(),
+            overload_name: row.try_get("overload_name")?,
+            is_procedure,
+            args,
+            return_type: if is_procedure {
+                None
+            } else {
+                Some(row.try_get("return_type")?)
+            },
+            returns_set: row.try_get("returns_set")?,
+            composite_columns: row.try_get("composite_columns")?,
+        })
+    }
+}
+
+// Quote an identifier in the same way that Postgres' quote_ident would
+pub fn quote_ident(ident: &str) -> String {
+    let is_simple = ident
+        .chars()
+        .next()
+        .map(|c| c.is_ascii_lowercase() || c == '_')
+        .unwrap_or(false)
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '$');
+
+    if is_simple {
+        return ident.to_string();
+    }
+
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+pub fn unquote_ident(ident: &str) -> String {
+    let ident = ident.trim();
+    if ident.len() >= 2 && ident.starts_with('"') && ident.ends_with('"') {
+        return ident[1..ident.len() - 1].replace("\"\"", "\"");
+    }
+    ident.to_lowercase()
+}
+
+// Find the position of the bracket closing the one opened at open_pos. Brackets within quotes are
+// ignored
+pub fn find_closing_bracket(text: &str, open_pos: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (idx, c) in text[open_pos..].char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => continue,
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open_pos + idx);
+                }
+            }
+            _ => continue,
+        }
+    }
+    None
+}
+
+// Split the text on the separator ignoring any separators found within brackets or quotes
+pub fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut part_start = 0;
+    for (idx, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => continue,
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, c) if c == separator && depth == 0 => {
+                parts.push(&text[part_start..idx]);
+                part_start = idx + c.len_utf8();
+            }
+            _ => continue,
+        }
+    }
+    parts.push(&text[part_start..]);
+    parts
+}
+
+// Split on whitespace while keeping quoted identifiers, string literals and bracketed type modifiers
+// together
+pub fn split_words(text: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut word_start: Option<usize> = None;
+    for (idx, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, c) if c.is_whitespace() && depth == 0 => {
+                if let Some(start) = word_start.take() {
+                    words.push(&text[start..idx]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        if word_start.is_none() {
+            word_start = Some(idx);
+        }
+    }
+    if let Some(start) = word_start {
+        words.push(&text[start..]);
+    }
+    words
+}
+
+fn parse_arg(arg: &str, default_mode: ArgMode) -> Result<FunctionArg> {
+    let arg = arg.trim();
+    let upper_arg = arg.to_uppercase();
+
+    // Strip the default value from the argument if there is one
+    let (arg, has_default) = match (upper_arg.find(" DEFAULT "), upper_arg.find(" = ")) {
+        (Some(pos), _) | (None, Some(pos)) => (arg[..pos].trim(), true),
+        (None, None) => (arg, false),
+    };
+
+    let mut words = split_words(arg);
+    if words.is_empty() {
+        bail!("The argument '{}' could not be parsed", arg);
+    }
+
+    let mode = match words[0].to_uppercase().as_str() {
+        "IN" => Some(ArgMode::In),
+        "OUT" => Some(ArgMode::Out),
+        "INOUT" => Some(ArgMode::InOut),
+        "VARIADIC" => Some(ArgMode::Variadic),
+        _ => None,
+    };
+    let mode = match mode {
+        Some(mode) if words.len() > 1 => {
+            words.remove(0);
+            mode
+        }
+        _ => default_mode,
+    };
+
+    let joined = words.join(" ").to_lowercase();
+    let is_unnamed = words.len() == 1
+        || MULTI_WORD_TYPES
+            .iter()
+            .any(|multi_word_type| joined.starts_with(multi_word_type));
+
+    let (name, arg_type) = if is_unnamed {
+        (None, words.join(" "))
+    } else {
+        (Some(unquote_ident(words[0])), words[1..].join(" "))
+    };
+
+    Ok(FunctionArg {
+        name,
+        arg_type,
+        mode,
+        has_default,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_definition_works() {
+        let input = r#"CREATE OR REPLACE FUNCTION public.concatenating(var1 text, var2 numeric(10,2) DEFAULT 1.5, double precision)
+ RETURNS text
+ LANGUAGE plpgsql
+AS $function$
+	BEGIN
+		return var1 || var2;
+	END
+$function$"#;
+
+        let signature = FunctionSignature::from_definition(
+            "public",
+            "concatenating(text, numeric, double precision)",
+            input,
+        )
+        .unwrap();
+
+        assert_eq!(signature.name, "concatenating");
+        assert!(!signature.is_procedure);
+        assert!(!signature.returns_set);
+        assert_eq!(signature.return_type, Some(String::from("text")));
+        assert_eq!(
+            signature.args,
+            vec![
+                FunctionArg {
+                    name: Some(String::from("var1")),
+                    arg_type: String::from("text"),
+                    mode: ArgMode::In,
+                    has_default: false,
+                },
+                FunctionArg {
+                    name: Some(String::from("var2")),
+                    arg_type: String::from("numeric(10,2)"),
+                    mode: ArgMode::In,
+                    has_default: true,
+                },
+                FunctionArg {
+                    name: None,
+                    arg_type: String::from("double precision"),
+                    mode: ArgMode::In,
+                    has_default: false,
+                },
+            ]
+        );
+        assert_eq!(signature.output_columns(), vec!["concatenating"]);
+    }
+
+    #[test]
+    fn from_definition_with_returns_table_works() {
+        let input = r#"CREATE OR REPLACE FUNCTION "My Schema".get_people(IN min_age integer, OUT total bigint)
+ RETURNS TABLE(name text, age integer)
+ LANGUAGE sql
+AS $function$ SELECT name, age FROM people $function$"#;
+
+        let signature =
+            FunctionSignature::from_definition("My Schema", "get_people(integer)", input).unwrap();
+
+        assert!(signature.returns_set);
+        assert_eq!(
+            signature
+                .input_args()
+                .map(|arg| arg.name.clone().unwrap())
+                .collect::<Vec<String>>(),
+            vec!["min_age"]
+        );
+        assert_eq!(signature.output_columns(), vec!["total", "name", "age"]);
+    }
+
+    #[test]
+    fn from_definition_with_procedure_works() {
+        let input = r#"CREATE OR REPLACE PROCEDURE public.insert_person(IN person_name text, INOUT inserted_id bigint)
+ LANGUAGE plpgsql
+AS $procedure$
+BEGIN
+    INSERT INTO public.people(name) VALUES (person_name) RETURNING id INTO inserted_id;
+END
+$procedure$"#;
+
+        let signature =
+            FunctionSignature::from_definition("public", "insert_person(text, bigint)", input)
+                .unwrap();
+
+        assert!(signature.is_procedure);
+        assert_eq!(signature.return_type, None);
+        assert_eq!(signature.args[1].mode, ArgMode::InOut);
+        assert_eq!(signature.output_columns(), vec!["inserted_id"]);
+    }
+
+    #[test]
+    fn quote_ident_works() {
+        assert_eq!(quote_ident("simple_name1"), "simple_name1");
+        assert_eq!(quote_ident("MixedCase"), "\"MixedCase\"");
+        assert_eq!(quote_ident("has \"quotes\""), "\"has \"\"quotes\"\"\"");
+    }
+}
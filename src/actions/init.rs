@@ -4,6 +4,14 @@ use colored::Colorize;
 pub const ENV_LOCATION: &str = "./.tusk/.env";
 pub const USER_CONFIG_LOCATION: &str = "./.tusk/user_config.yaml";
 pub const SCHEMA_CONFIG_LOCATION: &str = "./.tusk/config/schemas_to_include.conf";
+pub const EXTENSION_CONFIG_LOCATION: &str = "./.tusk/config/extensions_to_include.conf";
+pub const DATABASE_SETTING_CONFIG_LOCATION: &str =
+    "./.tusk/config/database_settings_to_include.conf";
+pub const FOREIGN_DATA_WRAPPER_CONFIG_LOCATION: &str =
+    "./.tusk/config/foreign_data_wrappers_to_include.conf";
+pub const FOREIGN_SERVER_CONFIG_LOCATION: &str = "./.tusk/config/foreign_servers_to_include.conf";
+pub const USER_MAPPING_CONFIG_LOCATION: &str = "./.tusk/config/user_mappings_to_include.conf";
+pub const DATABASE_DDL_LOCATION: &str = "./database";
 
 #[derive(Debug, Args)]
 pub struct Init {}
@@ -36,17 +44,40 @@ fetch_options:
       table_ddl: false
       table_data: true
       views: false
+      materialized_views: false
       data_types: false
+      triggers: false
+      sequences: false
+      indexes: false
+      aggregates: false
+      operators: false
+      casts: false
+      event_triggers: false
+      foreign_tables: false
+      extensions: false
+      database_settings: true # Most settings are tuning specific to the environment, so only the chosen ones should be tracked
+      foreign_data_wrappers: false
+      foreign_servers: false
+      user_mappings: true # Mappings depend on the roles of the environment, so only the chosen ones should be tracked
     delete_items_from_config: true # Decide if items that exist in the config files but not on the DB should be deleted from the config files on fetch
+    include_partitions: false # List each partition of a partitioned table in the table_ddl config files rather than only the partitioned table itself
 
 pull_options:
     clean_ddl_before_pulling: true # Delete files before repopulating with pull. Functions will only be deleted if there aren't unit tests defined for the function
     confirm_before_pull: false # Require confirmation of what ddl will be pulled before commencing pulling 
+    include_sequence_values: false # Add a setval to each pulled sequence so that it is restored with its current value
+    exclude_indexes_from_table_ddl: false # Leave indexes out of the table ddl files so they are only found in the indexes directory
+    exclude_triggers_from_table_ddl: false # Leave triggers out of the table ddl files so they are only found in the triggers directory
+    include_partition_summary: false # Write the partitions of each tracked partitioned table, along with their bounds, to the partitions directory
     pg_dump_additional_args: # These are additional user args that can be added to the calls to pg_dump
 
 push_options:
     test_after_push: true # Run unit tests after pushing the functions. If ANY of the tests fail then all of the functions that were pushed will be rolled back.
     confirm_before_push: false # Require confirmation of what functions will be pushed before commencing pushing 
+    sync_doc_comments: false # Set the COMMENT ON of each pushed function to its doc comment within the push transaction
+
+doc_options:
+    comment_style: block # Where the doc comments of functions are written. One of block (/** */ within the function), line (-- lines within the function) or comment_on (COMMENT ON FUNCTION)
                 "#,
             )?;
             println!("\tCreated file: {}", USER_CONFIG_LOCATION.bold());
@@ -61,6 +92,9 @@ push_options:
         std::fs::create_dir_all("./schemas")?;
         println!("\tCreated directory: {}", "./schemas".bold());
 
+        std::fs::create_dir_all(DATABASE_DDL_LOCATION)?;
+        println!("\tCreated directory: {}", DATABASE_DDL_LOCATION.bold());
+
         Ok(())
     }
 
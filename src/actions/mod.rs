@@ -4,3 +4,4 @@ pub mod pull;
 pub mod push;
 pub mod unit_test;
 pub mod doc;
+pub mod function_signature;
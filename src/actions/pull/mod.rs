@@ -17,11 +17,33 @@ use crate::{
 };
 
 use self::pullers::{
-    data_type_puller::DataTypePuller, function_puller::FunctionPuller,
-    table_data_puller::TableDataPuller, table_ddl_puller::TableDDLPuller, view_puller::ViewPuller,
+    aggregate_puller::AggregatePuller,
+    cast_puller::CastPuller,
+    data_type_puller::DataTypePuller,
+    database_puller::{DatabaseSettingPuller, ExtensionPuller, SchemaPuller},
+    event_trigger_puller::EventTriggerPuller,
+    foreign_data_puller::{
+        ForeignDataWrapperPuller, ForeignServerPuller, ForeignTablePuller, UserMappingPuller,
+    },
+    function_puller::FunctionPuller,
+    index_puller::IndexPuller,
+    materialized_view_puller::MaterializedViewPuller,
+    operator_puller::OperatorPuller,
+    partition_puller::PartitionPuller,
+    security_puller::{
+        DataTypeSecurityPuller, ForeignTableSecurityPuller, FunctionSecurityPuller,
+        MaterializedViewSecurityPuller, TableSecurityPuller, ViewSecurityPuller,
+    },
+    sequence_puller::SequencePuller, table_data_puller::TableDataPuller,
+    table_ddl_puller::TableDDLPuller, trigger_puller::TriggerPuller, view_puller::ViewPuller,
+    DatabaseSQLPuller,
 };
 
-use super::init::SCHEMA_CONFIG_LOCATION;
+use super::init::{
+    DATABASE_DDL_LOCATION, DATABASE_SETTING_CONFIG_LOCATION, EXTENSION_CONFIG_LOCATION,
+    FOREIGN_DATA_WRAPPER_CONFIG_LOCATION, FOREIGN_SERVER_CONFIG_LOCATION, SCHEMA_CONFIG_LOCATION,
+    USER_MAPPING_CONFIG_LOCATION,
+};
 
 #[derive(sqlx::FromRow, Default, Debug)]
 pub struct DDL {
@@ -33,7 +55,7 @@ pub struct DDL {
 #[derive(Debug, Args)]
 pub struct Pull {
     /// Pull the specified function or functions that start with the input pattern.
-    #[arg(short,long, num_args(0..), required_unless_present_any(["table_ddl", "table_data", "data_types", "views", "all"]))]
+    #[arg(short,long, num_args(0..), required_unless_present_any(["table_ddl", "table_data", "data_types", "views", "materialized_views", "triggers", "sequences", "indexes", "aggregates", "operators", "casts", "event_triggers", "foreign_tables", "security", "database", "all"]))]
     functions: Option<Vec<String>>,
 
     /// Pull the specified table ddl that starts with the input pattern.
@@ -48,10 +70,59 @@ pub struct Pull {
     #[arg(short,long, num_args(0..))]
     data_types: Option<Vec<String>>,
 
-    /// Pull the specified views (materialized views and normal views) that starts with the input pattern.     
+    /// Pull the specified views that starts with the input pattern.
     #[arg(short,long, num_args(0..))]
     views: Option<Vec<String>>,
 
+    /// Pull the specified materialized views, along with their indexes, that start with the input
+    /// pattern.
+    #[arg(long, num_args(0..))]
+    materialized_views: Option<Vec<String>>,
+
+    /// Pull the specified triggers (table.trigger) that start with the input pattern.
+    #[arg(long, num_args(0..))]
+    triggers: Option<Vec<String>>,
+
+    /// Pull the specified sequences that start with the input pattern.
+    #[arg(long, num_args(0..))]
+    sequences: Option<Vec<String>>,
+
+    /// Pull the specified indexes that start with the input pattern.
+    #[arg(long, num_args(0..))]
+    indexes: Option<Vec<String>>,
+
+    /// Pull the specified aggregates that start with the input pattern.
+    #[arg(long, num_args(0..))]
+    aggregates: Option<Vec<String>>,
+
+    /// Pull the specified operators (name(left_type,right_type)) that start with the input pattern.
+    #[arg(long, num_args(0..))]
+    operators: Option<Vec<String>>,
+
+    /// Pull the specified casts (source_to_target) that start with the input pattern.
+    #[arg(long, num_args(0..))]
+    casts: Option<Vec<String>>,
+
+    /// Pull the specified event triggers that start with the input pattern.
+    #[arg(long, num_args(0..))]
+    event_triggers: Option<Vec<String>>,
+
+    /// Pull the specified foreign tables that start with the input pattern.
+    #[arg(long, num_args(0..))]
+    foreign_tables: Option<Vec<String>>,
+
+    /// Pull the row level security, policies and privileges of the specified tables, foreign
+    /// tables, views, materialized views, functions and data types that start with the input
+    /// pattern.
+    #[arg(long, num_args(0..))]
+    security: Option<Vec<String>>,
+
+    /// Pull the definitions of the schemas along with the extensions, database settings, foreign
+    /// data wrappers, foreign servers and user mappings that are uncommented in ./.tusk/config into
+    /// ./database
+    #[arg(long)]
+    database: bool,
+
     /// Pull all of the DDL within the schemas that are uncommented in the schema config file found
     /// at ./.tusk/config/schemas_to_include.conf
     #[arg(short, long, exclusive(true))]
@@ -88,36 +159,61 @@ impl Pull {
             self.get_items_to_pull(schema_name, config_file_path, ddl_parent_dir, input_items)?;
 
         if let Some(items_to_pull) = items_to_pull {
-            let mut all_ddl = T::get(pool, schema_name, &items_to_pull)?;
-
-            while let Some(ddl) = all_ddl.try_next().await? {
-                let file_path = format!("./schemas/{}/{}.sql", schema_name, ddl.file_path);
-                if ddl.definition.is_empty() {
-                    println!(
-                        "\t{} ({}): Does not exist within the database",
-                        "Warning".yellow(),
-                        file_path
-                    );
-                    continue;
-                }
-                let parent_dir =
-                    std::path::Path::new(&file_path)
-                        .parent()
-                        .ok_or(anyhow::Error::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!(
-                                "The directory {} is invalid for writing files to...",
-                                file_path
-                            ),
-                        )))?;
-
-                if !parent_dir.exists() {
-                    std::fs::create_dir_all(parent_dir)?;
-                }
+            Self::write_ddl(
+                T::get(pool, schema_name, &items_to_pull)?,
+                &format!("./schemas/{}", schema_name),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn pull_database_sql<T: pullers::DatabaseSQLPuller>(
+        &self,
+        pool: &PgPool,
+        config_file_path: &str,
+        ddl_parent_dir: &str,
+        input_items: &Option<Vec<String>>,
+    ) -> Result<()> {
+        let items_to_pull =
+            self.get_items_to_pull("database", config_file_path, ddl_parent_dir, input_items)?;
+
+        if let Some(items_to_pull) = items_to_pull {
+            Self::write_ddl(T::get(pool, &items_to_pull)?, DATABASE_DDL_LOCATION).await?;
+        }
+
+        Ok(())
+    }
 
-                println!("\tPulling {}", file_path.magenta());
-                std::fs::write(file_path, ddl.definition)?;
+    // Write each of the pulled items to its file path within the root directory
+    async fn write_ddl(mut all_ddl: pullers::RowStream<'_>, root_dir: &str) -> Result<()> {
+        while let Some(ddl) = all_ddl.try_next().await? {
+            let file_path = format!("{}/{}.sql", root_dir, ddl.file_path);
+            if ddl.definition.is_empty() {
+                println!(
+                    "\t{} ({}): Does not exist within the database",
+                    "Warning".yellow(),
+                    file_path
+                );
+                continue;
             }
+            let parent_dir =
+                std::path::Path::new(&file_path)
+                    .parent()
+                    .ok_or(anyhow::Error::new(std::io::Error::other(
+                        format!(
+                            "The directory {} is invalid for writing files to...",
+                            file_path
+                        ),
+                    )))?;
+
+            if !parent_dir.exists() {
+                std::fs::create_dir_all(parent_dir)?;
+            }
+
+            println!("\tPulling {}", file_path.magenta());
+            std::fs::write(file_path, ddl.definition)?;
         }
 
         Ok(())
@@ -195,6 +291,17 @@ impl Pull {
         ddl_parent_dir: &str,
         input_items: &Option<Vec<String>>,
     ) -> Result<Option<Vec<String>>> {
+        if !std::path::Path::new(config_file_path).exists() {
+            // Config files for newer item types only exist once fetch has been run again
+            if input_items.is_some() {
+                anyhow::bail!(
+                    "The config file {} does not exist. Run tusk fetch to create it",
+                    config_file_path
+                );
+            }
+            return Ok(None);
+        }
+
         format_config_file(config_file_path)?;
 
         match (self.all, input_items) {
@@ -258,21 +365,14 @@ impl Pull {
         }
     }
 
-    async fn create_schema_def(schema: &str) -> Result<()> {
-        let schema_ddl_dir = format!("./schemas/{}/{}.sql", schema, schema);
-        let parent_path = std::path::Path::new(&schema_ddl_dir)
-            .parent()
-            .expect("There is always a parent to the above path");
-        if !parent_path.exists() {
-            tokio::fs::create_dir_all(&parent_path).await?;
+    // Pull the full definitions of the schemas being version controlled
+    async fn pull_schema_defs(&self, pool: &PgPool, schemas: &[String]) -> Result<()> {
+        let schema_ddl_dir = format!("{}/schemas", DATABASE_DDL_LOCATION);
+        if self.clean_before_pull {
+            Self::clean_ddl_dir(&schema_ddl_dir)?;
         }
 
-        let ddl = format!("CREATE SCHEMA IF NOT EXISTS {};\n", schema);
-        tokio::fs::write(&schema_ddl_dir, ddl).await?;
-
-        println!("\tPulling {}", schema_ddl_dir.magenta());
-
-        Ok(())
+        Self::write_ddl(SchemaPuller::get(pool, schemas)?, DATABASE_DDL_LOCATION).await
     }
 
     pub async fn execute(&mut self) -> anyhow::Result<()> {
@@ -293,14 +393,56 @@ impl Pull {
 
         println!("\nBeginning Pulling:");
 
+        if self.all || self.database {
+            println!("\nBeginning database pull:");
+            self.pull_schema_defs(pool, &approved_schemas).await?;
+
+            // Config files that don't exist yet are only an error when the database was requested
+            let database_items = (!self.all).then(Vec::new);
+            self.pull_database_sql::<ExtensionPuller>(
+                pool,
+                EXTENSION_CONFIG_LOCATION,
+                &format!("{}/extensions", DATABASE_DDL_LOCATION),
+                &database_items,
+            )
+            .await?;
+
+            self.pull_database_sql::<DatabaseSettingPuller>(
+                pool,
+                DATABASE_SETTING_CONFIG_LOCATION,
+                &format!("{}/settings", DATABASE_DDL_LOCATION),
+                &database_items,
+            )
+            .await?;
+
+            self.pull_database_sql::<ForeignDataWrapperPuller>(
+                pool,
+                FOREIGN_DATA_WRAPPER_CONFIG_LOCATION,
+                &format!("{}/foreign_data_wrappers", DATABASE_DDL_LOCATION),
+                &database_items,
+            )
+            .await?;
+
+            self.pull_database_sql::<ForeignServerPuller>(
+                pool,
+                FOREIGN_SERVER_CONFIG_LOCATION,
+                &format!("{}/foreign_servers", DATABASE_DDL_LOCATION),
+                &database_items,
+            )
+            .await?;
+
+            self.pull_database_sql::<UserMappingPuller>(
+                pool,
+                USER_MAPPING_CONFIG_LOCATION,
+                &format!("{}/user_mappings", DATABASE_DDL_LOCATION),
+                &database_items,
+            )
+            .await?;
+        }
+
         for schema in approved_schemas {
             println!("\nBeginning {} schema pull:", schema);
 
-            if self.all {
-                // If we are syncing everything then also create the schema ddl file
-                Self::create_schema_def(&schema).await?;
-            }
-
             // get the function ddl
             self.pull_sql::<FunctionPuller>(
                 pool,
@@ -326,6 +468,21 @@ impl Pull {
             )
             .await?;
 
+            // get the summary of the partitions of each partitioned table
+            if UserConfig::get_global()?
+                .pull_options
+                .include_partition_summary
+            {
+                self.pull_sql::<PartitionPuller>(
+                    pool,
+                    &schema,
+                    &format!("./.tusk/config/schemas/{}/table_ddl_to_include.conf", schema),
+                    &format!("./schemas/{}/partitions", schema),
+                    &self.table_ddl,
+                )
+                .await?;
+            }
+
             // get the table data
             self.pull_pg_dump::<TableDataPuller>(
                 &schema,
@@ -359,6 +516,154 @@ impl Pull {
                 &self.views,
             )
             .await?;
+
+            // get the materialized view ddl
+            self.pull_pg_dump::<MaterializedViewPuller>(
+                &schema,
+                &format!(
+                    "./.tusk/config/schemas/{}/materialized_views_to_include.conf",
+                    schema,
+                ),
+                &format!("./schemas/{}/materialized_views", schema),
+                &self.materialized_views,
+            )
+            .await?;
+
+            // get the trigger ddl
+            self.pull_sql::<TriggerPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/triggers_to_include.conf", schema),
+                &format!("./schemas/{}/triggers", schema),
+                &self.triggers,
+            )
+            .await?;
+
+            // get the sequence ddl
+            self.pull_sql::<SequencePuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/sequences_to_include.conf", schema),
+                &format!("./schemas/{}/sequences", schema),
+                &self.sequences,
+            )
+            .await?;
+
+            // get the index ddl
+            self.pull_sql::<IndexPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/indexes_to_include.conf", schema),
+                &format!("./schemas/{}/indexes", schema),
+                &self.indexes,
+            )
+            .await?;
+
+            // get the aggregate ddl
+            self.pull_sql::<AggregatePuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/aggregates_to_include.conf", schema),
+                &format!("./schemas/{}/aggregates", schema),
+                &self.aggregates,
+            )
+            .await?;
+
+            // get the operator ddl
+            self.pull_sql::<OperatorPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/operators_to_include.conf", schema),
+                &format!("./schemas/{}/operators", schema),
+                &self.operators,
+            )
+            .await?;
+
+            // get the cast ddl
+            self.pull_sql::<CastPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/casts_to_include.conf", schema),
+                &format!("./schemas/{}/casts", schema),
+                &self.casts,
+            )
+            .await?;
+
+            // get the event trigger ddl
+            self.pull_sql::<EventTriggerPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/event_triggers_to_include.conf", schema),
+                &format!("./schemas/{}/event_triggers", schema),
+                &self.event_triggers,
+            )
+            .await?;
+
+            // get the foreign table ddl
+            self.pull_sql::<ForeignTablePuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/foreign_tables_to_include.conf", schema),
+                &format!("./schemas/{}/foreign_tables", schema),
+                &self.foreign_tables,
+            )
+            .await?;
+
+            // get the security of every tracked table, foreign table, view, materialized view, function and
+            // data type
+            self.pull_sql::<TableSecurityPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/table_ddl_to_include.conf", schema),
+                &format!("./schemas/{}/security/tables", schema),
+                &self.security,
+            )
+            .await?;
+
+            self.pull_sql::<ForeignTableSecurityPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/foreign_tables_to_include.conf", schema),
+                &format!("./schemas/{}/security/foreign_tables", schema),
+                &self.security,
+            )
+            .await?;
+
+            self.pull_sql::<ViewSecurityPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/views_to_include.conf", schema),
+                &format!("./schemas/{}/security/views", schema),
+                &self.security,
+            )
+            .await?;
+
+            self.pull_sql::<MaterializedViewSecurityPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/materialized_views_to_include.conf", schema),
+                &format!("./schemas/{}/security/materialized_views", schema),
+                &self.security,
+            )
+            .await?;
+
+            self.pull_sql::<FunctionSecurityPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/functions_to_include.conf", schema),
+                &format!("./schemas/{}/security/functions", schema),
+                &self.security,
+            )
+            .await?;
+
+            self.pull_sql::<DataTypeSecurityPuller>(
+                pool,
+                &schema,
+                &format!("./.tusk/config/schemas/{}/data_types_to_include.conf", schema),
+                &format!("./schemas/{}/security/data_types", schema),
+                &self.security,
+            )
+            .await?;
         }
         Ok(())
     }
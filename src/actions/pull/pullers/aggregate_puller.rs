@@ -0,0 +1,103 @@
+use crate::actions::pull::pullers::SQLPuller;
+
+// Aggregates have no equivalent of pg_get_functiondef so the CREATE AGGREGATE is rebuilt from
+// pg_aggregate. Options that are unset are NULL and so are left out by array_to_string
+const AGGREGATE_DDL_QUERY: &str = "
+        WITH procs AS (
+            SELECT
+                f.oid,
+                format('%I.%I', n.nspname, f.proname) AS name
+            FROM pg_proc f INNER JOIN pg_namespace n ON (f.pronamespace = n.oid)
+        ),
+        aggregates AS (
+            SELECT
+                p.oid,
+                p.proname,
+                ns.nspname,
+                oidvectortypes(p.proargtypes) AS argument_types,
+                CASE WHEN p.pronargs = 0 THEN '*' ELSE pg_get_function_arguments(p.oid) END AS arguments,
+                CASE WHEN p.pronargs = 0 THEN '*' ELSE pg_get_function_identity_arguments(p.oid) END AS identity_arguments,
+                array_to_string(ARRAY[
+                    'SFUNC = ' || tf.name,
+                    'STYPE = ' || format_type(a.aggtranstype, NULL),
+                    'SSPACE = ' || NULLIF(a.aggtransspace, 0),
+                    'FINALFUNC = ' || ff.name,
+                    CASE WHEN a.aggfinalextra THEN 'FINALFUNC_EXTRA' END,
+                    CASE WHEN a.aggfinalfn <> 0
+                        THEN 'FINALFUNC_MODIFY = ' || CASE a.aggfinalmodify WHEN 'r' THEN 'READ_ONLY' WHEN 's' THEN 'SHAREABLE' ELSE 'READ_WRITE' END
+                    END,
+                    'COMBINEFUNC = ' || cf.name,
+                    'SERIALFUNC = ' || sf.name,
+                    'DESERIALFUNC = ' || df.name,
+                    'INITCOND = ' || quote_literal(a.agginitval),
+                    'MSFUNC = ' || mtf.name,
+                    'MINVFUNC = ' || mif.name,
+                    'MSTYPE = ' || format_type(NULLIF(a.aggmtranstype, 0), NULL),
+                    'MSSPACE = ' || NULLIF(a.aggmtransspace, 0),
+                    'MFINALFUNC = ' || mff.name,
+                    CASE WHEN a.aggmfinalextra THEN 'MFINALFUNC_EXTRA' END,
+                    CASE WHEN a.aggmfinalfn <> 0
+                        THEN 'MFINALFUNC_MODIFY = ' || CASE a.aggmfinalmodify WHEN 'r' THEN 'READ_ONLY' WHEN 's' THEN 'SHAREABLE' ELSE 'READ_WRITE' END
+                    END,
+                    'MINITCOND = ' || quote_literal(a.aggminitval),
+                    'SORTOP = ' || (
+                        SELECT format('OPERATOR(%I.%s)', n.nspname, o.oprname)
+                        FROM pg_operator o INNER JOIN pg_namespace n ON (o.oprnamespace = n.oid)
+                        WHERE o.oid = a.aggsortop
+                    ),
+                    CASE p.proparallel WHEN 's' THEN 'PARALLEL = SAFE' WHEN 'r' THEN 'PARALLEL = RESTRICTED' END,
+                    CASE WHEN a.aggkind = 'h' THEN 'HYPOTHETICAL' END
+                ], E',\\n\\t') AS options
+            FROM pg_aggregate a
+            INNER JOIN pg_proc p ON (a.aggfnoid = p.oid)
+            INNER JOIN pg_namespace ns ON (p.pronamespace = ns.oid)
+            LEFT JOIN procs tf ON (a.aggtransfn = tf.oid)
+            LEFT JOIN procs ff ON (a.aggfinalfn = ff.oid)
+            LEFT JOIN procs cf ON (a.aggcombinefn = cf.oid)
+            LEFT JOIN procs sf ON (a.aggserialfn = sf.oid)
+            LEFT JOIN procs df ON (a.aggdeserialfn = df.oid)
+            LEFT JOIN procs mtf ON (a.aggmtransfn = mtf.oid)
+            LEFT JOIN procs mif ON (a.aggminvtransfn = mif.oid)
+            LEFT JOIN procs mff ON (a.aggmfinalfn = mff.oid)
+            WHERE ns.nspname = $1
+            AND p.proname IN (SELECT * FROM UNNEST($2))
+            AND NOT EXISTS (
+                SELECT 1
+                FROM pg_depend d
+                WHERE d.classid = 'pg_proc'::regclass
+                AND d.objid = p.oid
+                AND d.deptype = 'e'
+            )
+        )
+        SELECT
+            format('%I(%s)', proname, argument_types) AS name,
+            format(E'CREATE OR REPLACE AGGREGATE %I.%I(%s) (\\n\\t%s\\n);\\n', nspname, proname, arguments, options)
+            || CASE WHEN obj_description(oid, 'pg_proc') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON AGGREGATE %I.%I(%s) IS %L;\\n', nspname, proname, identity_arguments, obj_description(oid, 'pg_proc'))
+                ELSE ''
+            END AS definition,
+            format('aggregates/%I/%I(%s)', proname, proname, argument_types) AS file_path
+        FROM aggregates
+        UNION
+        SELECT '', '', format('aggregates/%s', aggregate_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($2) aggregate_name
+        ) names
+        WHERE names.aggregate_name NOT IN (
+            SELECT
+                p.proname
+            FROM pg_proc p INNER JOIN pg_namespace ns ON (p.pronamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND p.prokind = 'a'
+        )
+            ";
+
+pub struct AggregatePuller {}
+
+impl SQLPuller for AggregatePuller {
+    fn get_ddl_query() -> &'static str {
+        AGGREGATE_DDL_QUERY
+    }
+}
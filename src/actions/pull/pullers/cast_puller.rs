@@ -0,0 +1,85 @@
+use crate::actions::pull::pullers::SQLPuller;
+
+// Casts don't belong to a schema so each user defined cast is tracked in the schema of its source
+// type, or its target type if the source is a built in type, or otherwise the schema of its
+// function. Casts are identified by source_to_target using the internal type names. Types outside
+// of pg_catalog are schema qualified so that the definition doesn't depend on the search_path
+const CAST_DDL_QUERY: &str = "
+        WITH casts AS (
+            SELECT
+                c.*,
+                format('%s_to_%s', st.typname, tt.typname) AS item_name,
+                CASE WHEN sns.nspname = 'pg_catalog'
+                    THEN format_type(c.castsource, NULL)
+                    ELSE format('%I.%I', sns.nspname, st.typname)
+                END AS source_type,
+                CASE WHEN tns.nspname = 'pg_catalog'
+                    THEN format_type(c.casttarget, NULL)
+                    ELSE format('%I.%I', tns.nspname, tt.typname)
+                END AS target_type,
+                CASE
+                    WHEN sns.nspname <> 'pg_catalog' THEN sns.nspname
+                    WHEN tns.nspname <> 'pg_catalog' THEN tns.nspname
+                    ELSE fns.nspname
+                END AS cast_schema,
+                format('%I.%I(%s)', fns.nspname, f.proname, oidvectortypes(f.proargtypes)) AS function_name
+            FROM pg_cast c
+            INNER JOIN pg_type st ON (c.castsource = st.oid)
+            INNER JOIN pg_namespace sns ON (st.typnamespace = sns.oid)
+            INNER JOIN pg_type tt ON (c.casttarget = tt.oid)
+            INNER JOIN pg_namespace tns ON (tt.typnamespace = tns.oid)
+            LEFT JOIN pg_proc f ON (c.castfunc = f.oid)
+            LEFT JOIN pg_namespace fns ON (f.pronamespace = fns.oid)
+            WHERE c.oid >= 16384
+            AND NOT EXISTS (
+                SELECT 1
+                FROM pg_depend d
+                WHERE d.classid = 'pg_cast'::regclass
+                AND d.objid = c.oid
+                AND d.deptype = 'e'
+            )
+        )
+        SELECT
+            c.item_name AS name,
+            format(E'DROP CAST IF EXISTS (%s AS %s);\\n\\n', c.source_type, c.target_type)
+            || format(
+                E'CREATE CAST (%s AS %s) %s%s;\\n',
+                c.source_type,
+                c.target_type,
+                CASE c.castmethod
+                    WHEN 'f' THEN 'WITH FUNCTION ' || c.function_name
+                    WHEN 'i' THEN 'WITH INOUT'
+                    ELSE 'WITHOUT FUNCTION'
+                END,
+                CASE c.castcontext WHEN 'a' THEN ' AS ASSIGNMENT' WHEN 'i' THEN ' AS IMPLICIT' ELSE '' END
+            )
+            || CASE WHEN obj_description(c.oid, 'pg_cast') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON CAST (%s AS %s) IS %L;\\n', c.source_type, c.target_type, obj_description(c.oid, 'pg_cast'))
+                ELSE ''
+            END AS definition,
+            format('casts/%s', c.item_name) AS file_path
+        FROM casts c
+        WHERE c.cast_schema = $1
+        AND c.item_name IN (SELECT * FROM UNNEST($2))
+        UNION
+        SELECT '', '', format('casts/%s', cast_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($2) cast_name
+        ) names
+        WHERE names.cast_name NOT IN (
+            SELECT
+                c.item_name
+            FROM casts c
+            WHERE c.cast_schema = $1
+        )
+            ";
+
+pub struct CastPuller {}
+
+impl SQLPuller for CastPuller {
+    fn get_ddl_query() -> &'static str {
+        CAST_DDL_QUERY
+    }
+}
@@ -15,6 +15,7 @@ const DATA_TYPE_QUERY:&str = "
             type_info AS (
                 SELECT 
                     type_schema,
+                    pt.oid AS type_oid,
                     a.attrelid::regclass AS type_name,
                     E'\t' || attname || ' ' || FORMAT_TYPE(a.atttypid, a.atttypmod) AS attr_def
                 FROM pg_type pt
@@ -33,14 +34,15 @@ const DATA_TYPE_QUERY:&str = "
                             SPLIT_PART(type_name::TEXT, '.', 2) 
                         ELSE type_name::TEXT END 
                     AS name, 
-                    'CREATE TYPE ' || type_name || E' AS (\n' ||ARRAY_TO_STRING(ARRAY_AGG(attr_def), E',\n') || E'\n);' AS definition,
+                    'CREATE TYPE ' || type_name || E' AS (\n' ||ARRAY_TO_STRING(ARRAY_AGG(attr_def), E',\n') || E'\n);'
+                    || COALESCE(E'\n\nCOMMENT ON TYPE ' || type_name || ' IS ' || QUOTE_LITERAL(OBJ_DESCRIPTION(type_oid, 'pg_type')) || ';', '') AS definition,
                     CASE
                         WHEN type_name::TEXT ILIKE '%.%' THEN 
                             'data_types/' || SPLIT_PART(type_name::TEXT, '.', 2)
                         ELSE 'data_types/' || type_name::TEXT END 
                     AS file_path
                 FROM type_info
-                GROUP BY type_name, type_schema
+                GROUP BY type_name, type_schema, type_oid
             ),
             domain_defs AS (
                 SELECT
@@ -54,8 +56,9 @@ const DATA_TYPE_QUERY:&str = "
                     || CASE WHEN pg_type.typnotnull THEN ' NOT NULL' ELSE '' END || E'\n'
                     || COALESCE(' COLLATE ' || QUOTE_IDENT(pg_collation.collname) || E'\n', '')
                     || COALESCE(' DEFAULT ' || pg_type.typdefault || E'\n', '')
-                    || COALESCE(string_agg('CONSTRAINT ' || pg_constraint.conname || ' ' || pg_get_constraintdef(pg_constraint.oid, true), '' ORDER BY pg_constraint.oid), '')
-                    || ';' AS definition,
+                    || COALESCE(string_agg('CONSTRAINT ' || pg_constraint.conname || ' ' || pg_get_constraintdef(pg_constraint.oid, true), E'\n' ORDER BY pg_constraint.oid), '')
+                    || ';'
+                    || COALESCE(E'\n\nCOMMENT ON DOMAIN ' || QUOTE_IDENT(schemas.nspname) || '.' || QUOTE_IDENT(pg_type.typname) || ' IS ' || QUOTE_LITERAL(OBJ_DESCRIPTION(pg_type.oid, 'pg_type')) || ';', '') AS definition,
                     'data_types/' || pg_type.typname AS file_path
                 FROM pg_type
                 LEFT JOIN pg_namespace AS schemas ON schemas.oid = pg_type.typnamespace
@@ -84,7 +87,8 @@ const DATA_TYPE_QUERY:&str = "
                     || QUOTE_IDENT(pg_type.typname)
                     || ' AS ENUM ('
                     ||  STRING_AGG(QUOTE_LITERAL(pg_enum.enumlabel::TEXT), ', ' ORDER BY pg_enum.enumsortorder)
-                    || ');' AS definition,
+                    || ');'
+                    || COALESCE(E'\n\nCOMMENT ON TYPE ' || QUOTE_IDENT(schemas.nspname) || '.' || QUOTE_IDENT(pg_type.typname) || ' IS ' || QUOTE_LITERAL(OBJ_DESCRIPTION(pg_type.oid, 'pg_type')) || ';', '') AS definition,
                     'data_types/' || pg_type.typname AS file_path
                 FROM pg_enum
                 JOIN pg_type ON pg_type.oid = pg_enum.enumtypid
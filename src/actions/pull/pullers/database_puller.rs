@@ -0,0 +1,168 @@
+use crate::actions::pull::pullers::DatabaseSQLPuller;
+
+// The full definition of each schema. The privileges on the schema are written as a REVOKE from
+// PUBLIC followed by a GRANT per grantee, and the default privileges of the schema as an ALTER
+// DEFAULT PRIVILEGES per role, so that the output is deterministic
+const SCHEMA_DDL_QUERY: &str = "
+        SELECT
+            ns.nspname AS name,
+            format(
+                E'CREATE SCHEMA IF NOT EXISTS %I;\\n\\nALTER SCHEMA %I OWNER TO %I;\\n',
+                ns.nspname,
+                ns.nspname,
+                pg_get_userbyid(ns.nspowner)
+            )
+            || CASE WHEN obj_description(ns.oid, 'pg_namespace') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON SCHEMA %I IS %L;\\n', ns.nspname, obj_description(ns.oid, 'pg_namespace'))
+                ELSE ''
+            END
+            || format(E'\\nREVOKE ALL ON SCHEMA %I FROM PUBLIC;\\n', ns.nspname)
+            || COALESCE((
+                SELECT
+                    string_agg(
+                        format(
+                            E'GRANT %s ON SCHEMA %I TO %s%s;\\n',
+                            g.privileges,
+                            ns.nspname,
+                            g.grantee,
+                            CASE WHEN g.is_grantable THEN ' WITH GRANT OPTION' ELSE '' END
+                        ),
+                        '' ORDER BY g.grantee, g.is_grantable
+                    )
+                FROM (
+                    SELECT
+                        CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE quote_ident(pg_get_userbyid(a.grantee)) END AS grantee,
+                        a.is_grantable,
+                        string_agg(a.privilege_type, ', ' ORDER BY a.privilege_type) AS privileges
+                    FROM aclexplode(COALESCE(ns.nspacl, acldefault('n', ns.nspowner))) a
+                    WHERE a.grantee <> ns.nspowner
+                    GROUP BY 1, 2
+                ) g
+            ), '')
+            || COALESCE((
+                SELECT
+                    E'\\n' || string_agg(
+                        format(
+                            E'ALTER DEFAULT PRIVILEGES FOR ROLE %I IN SCHEMA %I GRANT %s ON %s TO %s%s;\\n',
+                            pg_get_userbyid(d.role),
+                            ns.nspname,
+                            d.privileges,
+                            d.object_type,
+                            d.grantee,
+                            CASE WHEN d.is_grantable THEN ' WITH GRANT OPTION' ELSE '' END
+                        ),
+                        '' ORDER BY pg_get_userbyid(d.role), d.object_type, d.grantee, d.is_grantable
+                    )
+                FROM (
+                    SELECT
+                        dacl.defaclrole AS role,
+                        CASE dacl.defaclobjtype
+                            WHEN 'r' THEN 'TABLES'
+                            WHEN 'S' THEN 'SEQUENCES'
+                            WHEN 'f' THEN 'FUNCTIONS'
+                            WHEN 'T' THEN 'TYPES'
+                        END AS object_type,
+                        CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE quote_ident(pg_get_userbyid(a.grantee)) END AS grantee,
+                        a.is_grantable,
+                        string_agg(a.privilege_type, ', ' ORDER BY a.privilege_type) AS privileges
+                    FROM pg_default_acl dacl
+                    CROSS JOIN aclexplode(dacl.defaclacl) a
+                    WHERE dacl.defaclnamespace = ns.oid
+                    GROUP BY 1, 2, 3, 4
+                ) d
+            ), '') AS definition,
+            format('schemas/%s', ns.nspname) AS file_path
+        FROM pg_namespace ns
+        WHERE ns.nspname IN (SELECT * FROM UNNEST($1))
+            ";
+
+// The schema of the extension is created first when it isn't one of the tracked schemas
+const EXTENSION_DDL_QUERY: &str = "
+        SELECT
+            e.extname AS name,
+            CASE WHEN ns.nspname <> 'pg_catalog'
+                THEN format(E'CREATE SCHEMA IF NOT EXISTS %I;\\n\\n', ns.nspname)
+                ELSE ''
+            END
+            || format(E'CREATE EXTENSION IF NOT EXISTS %I WITH SCHEMA %I VERSION %L;\\n', e.extname, ns.nspname, e.extversion) AS definition,
+            format('extensions/%s', e.extname) AS file_path
+        FROM pg_extension e
+        INNER JOIN pg_namespace ns ON (e.extnamespace = ns.oid)
+        WHERE e.extname IN (SELECT * FROM UNNEST($1))
+        UNION
+        SELECT '', '', format('extensions/%s', extension_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($1) extension_name
+        ) names
+        WHERE names.extension_name NOT IN (
+            SELECT
+                e.extname
+            FROM pg_extension e
+        )
+            ";
+
+// Settings that are lists are stored with their elements already quoted and so can't be quoted as
+// a single literal
+const DATABASE_SETTING_DDL_QUERY: &str = "
+        WITH settings AS (
+            SELECT
+                split_part(setting, '=', 1) AS name,
+                substr(setting, strpos(setting, '=') + 1) AS value
+            FROM pg_db_role_setting s
+            CROSS JOIN UNNEST(s.setconfig) setting
+            WHERE s.setdatabase = (SELECT oid FROM pg_database WHERE datname = current_database())
+            AND s.setrole = 0
+        )
+        SELECT
+            s.name,
+            format(
+                E'ALTER DATABASE %I SET %s = %s;\\n',
+                current_database(),
+                s.name,
+                CASE WHEN s.name IN ('search_path', 'temp_tablespaces', 'session_preload_libraries', 'local_preload_libraries')
+                    THEN s.value
+                    ELSE quote_literal(s.value)
+                END
+            ) AS definition,
+            format('settings/%s', s.name) AS file_path
+        FROM settings s
+        WHERE s.name IN (SELECT * FROM UNNEST($1))
+        UNION
+        SELECT '', '', format('settings/%s', setting_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($1) setting_name
+        ) names
+        WHERE names.setting_name NOT IN (
+            SELECT
+                s.name
+            FROM settings s
+        )
+            ";
+
+pub struct SchemaPuller {}
+
+impl DatabaseSQLPuller for SchemaPuller {
+    fn get_ddl_query() -> &'static str {
+        SCHEMA_DDL_QUERY
+    }
+}
+
+pub struct ExtensionPuller {}
+
+impl DatabaseSQLPuller for ExtensionPuller {
+    fn get_ddl_query() -> &'static str {
+        EXTENSION_DDL_QUERY
+    }
+}
+
+pub struct DatabaseSettingPuller {}
+
+impl DatabaseSQLPuller for DatabaseSettingPuller {
+    fn get_ddl_query() -> &'static str {
+        DATABASE_SETTING_DDL_QUERY
+    }
+}
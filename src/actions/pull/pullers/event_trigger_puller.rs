@@ -0,0 +1,73 @@
+use crate::actions::pull::pullers::SQLPuller;
+
+// Event triggers don't belong to a schema so each is tracked in the schema of the function that it
+// executes. The definition drops the event trigger first as there is no CREATE OR REPLACE
+const EVENT_TRIGGER_DDL_QUERY: &str = "
+        WITH event_triggers AS (
+            SELECT
+                e.*,
+                ns.nspname AS function_schema,
+                f.proname AS function_name
+            FROM pg_event_trigger e
+            INNER JOIN pg_proc f ON (e.evtfoid = f.oid)
+            INNER JOIN pg_namespace ns ON (f.pronamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND NOT EXISTS (
+                SELECT 1
+                FROM pg_depend d
+                WHERE d.classid = 'pg_event_trigger'::regclass
+                AND d.objid = e.oid
+                AND d.deptype = 'e'
+            )
+        )
+        SELECT
+            e.evtname AS name,
+            format(E'DROP EVENT TRIGGER IF EXISTS %I;\\n\\n', e.evtname)
+            || format(
+                E'CREATE EVENT TRIGGER %I ON %I%s\\n\\tEXECUTE FUNCTION %I.%I();\\n',
+                e.evtname,
+                e.evtevent,
+                CASE WHEN e.evttags IS NOT NULL
+                    THEN format(
+                        E'\\n\\tWHEN TAG IN (%s)',
+                        array_to_string(ARRAY(SELECT quote_literal(tag) FROM UNNEST(e.evttags) tag), ', ')
+                    )
+                    ELSE ''
+                END,
+                e.function_schema,
+                e.function_name
+            )
+            || CASE e.evtenabled
+                WHEN 'D' THEN format(E'\\nALTER EVENT TRIGGER %I DISABLE;\\n', e.evtname)
+                WHEN 'R' THEN format(E'\\nALTER EVENT TRIGGER %I ENABLE REPLICA;\\n', e.evtname)
+                WHEN 'A' THEN format(E'\\nALTER EVENT TRIGGER %I ENABLE ALWAYS;\\n', e.evtname)
+                ELSE ''
+            END
+            || CASE WHEN obj_description(e.oid, 'pg_event_trigger') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON EVENT TRIGGER %I IS %L;\\n', e.evtname, obj_description(e.oid, 'pg_event_trigger'))
+                ELSE ''
+            END AS definition,
+            format('event_triggers/%s', e.evtname) AS file_path
+        FROM event_triggers e
+        WHERE e.evtname IN (SELECT * FROM UNNEST($2))
+        UNION
+        SELECT '', '', format('event_triggers/%s', event_trigger_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($2) event_trigger_name
+        ) names
+        WHERE names.event_trigger_name NOT IN (
+            SELECT
+                e.evtname
+            FROM event_triggers e
+        )
+            ";
+
+pub struct EventTriggerPuller {}
+
+impl SQLPuller for EventTriggerPuller {
+    fn get_ddl_query() -> &'static str {
+        EVENT_TRIGGER_DDL_QUERY
+    }
+}
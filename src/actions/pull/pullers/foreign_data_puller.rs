@@ -0,0 +1,314 @@
+use crate::actions::pull::pullers::{DatabaseSQLPuller, SQLPuller};
+
+// Builds the OPTIONS clause from an array of options as stored in the catalog. Only the options
+// matching the kept condition are written, which by default leaves out options that look like
+// secrets, such as passwords, so that they are never written to the repo
+macro_rules! options_clause {
+    ($options:literal, $open:literal, $separator:literal, $close:literal) => {
+        options_clause!(
+            $options,
+            "opt.option_name !~* '(pass|secret|token|key)'",
+            $open,
+            $separator,
+            $close
+        )
+    };
+    ($options:literal, $kept:literal, $open:literal, $separator:literal, $close:literal) => {
+        concat!(
+            "COALESCE(E' OPTIONS (",
+            $open,
+            "' || NULLIF(array_to_string(ARRAY(
+                SELECT format('%I %L', opt.option_name, opt.option_value)
+                FROM pg_options_to_table(",
+            $options,
+            ") opt
+                WHERE ",
+            $kept,
+            "
+            ), E'",
+            $separator,
+            "'), '') || E'",
+            $close,
+            ")', '')"
+        )
+    };
+}
+
+// Lists the names of the options that were left out of the OPTIONS clause as a comment so that
+// it is clear they need to be set on the database
+macro_rules! redacted_options {
+    ($options:literal) => {
+        redacted_options!($options, "opt.option_name !~* '(pass|secret|token|key)'")
+    };
+    ($options:literal, $kept:literal) => {
+        concat!(
+            "COALESCE(E'-- Redacted options: ' || NULLIF(array_to_string(ARRAY(
+                SELECT opt.option_name
+                FROM pg_options_to_table(",
+            $options,
+            ") opt
+                WHERE NOT (",
+            $kept,
+            ")
+            ), ', '), '') || E'\\n', '')"
+        )
+    };
+}
+
+// Foreign data wrappers created by an extension, such as postgres_fdw, are created with the
+// extension and are left out
+const FOREIGN_DATA_WRAPPER_DDL_QUERY: &str = concat!(
+    "
+        WITH wrappers AS (
+            SELECT
+                w.*
+            FROM pg_foreign_data_wrapper w
+            WHERE NOT EXISTS (
+                SELECT 1
+                FROM pg_depend d
+                WHERE d.classid = 'pg_foreign_data_wrapper'::regclass
+                AND d.objid = w.oid
+                AND d.deptype = 'e'
+            )
+        )
+        SELECT
+            w.fdwname AS name,
+            ",
+    redacted_options!("w.fdwoptions"),
+    "
+            || format(E'CREATE FOREIGN DATA WRAPPER %I', w.fdwname)
+            || COALESCE((
+                SELECT format(E'\\nHANDLER %I.%I', n.nspname, f.proname)
+                FROM pg_proc f INNER JOIN pg_namespace n ON (f.pronamespace = n.oid)
+                WHERE f.oid = w.fdwhandler
+            ), '')
+            || COALESCE((
+                SELECT format(E'\\nVALIDATOR %I.%I', n.nspname, f.proname)
+                FROM pg_proc f INNER JOIN pg_namespace n ON (f.pronamespace = n.oid)
+                WHERE f.oid = w.fdwvalidator
+            ), '')
+            || ",
+    options_clause!("w.fdwoptions", "\\n\\t", ",\\n\\t", "\\n"),
+    "
+            || format(E';\\n\\nALTER FOREIGN DATA WRAPPER %I OWNER TO %I;\\n', w.fdwname, pg_get_userbyid(w.fdwowner))
+            || CASE WHEN obj_description(w.oid, 'pg_foreign_data_wrapper') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON FOREIGN DATA WRAPPER %I IS %L;\\n', w.fdwname, obj_description(w.oid, 'pg_foreign_data_wrapper'))
+                ELSE ''
+            END AS definition,
+            format('foreign_data_wrappers/%s', w.fdwname) AS file_path
+        FROM wrappers w
+        WHERE w.fdwname IN (SELECT * FROM UNNEST($1))
+        UNION
+        SELECT '', '', format('foreign_data_wrappers/%s', wrapper_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($1) wrapper_name
+        ) names
+        WHERE names.wrapper_name NOT IN (
+            SELECT
+                w.fdwname
+            FROM wrappers w
+        )
+            "
+);
+
+const FOREIGN_SERVER_DDL_QUERY: &str = concat!(
+    "
+        SELECT
+            s.srvname AS name,
+            ",
+    redacted_options!("s.srvoptions"),
+    "
+            || format(E'CREATE SERVER IF NOT EXISTS %I', s.srvname)
+            || COALESCE(' TYPE ' || quote_literal(s.srvtype), '')
+            || COALESCE(' VERSION ' || quote_literal(s.srvversion), '')
+            || format(E'\\nFOREIGN DATA WRAPPER %I', w.fdwname)
+            || ",
+    options_clause!("s.srvoptions", "\\n\\t", ",\\n\\t", "\\n"),
+    "
+            || format(E';\\n\\nALTER SERVER %I OWNER TO %I;\\n', s.srvname, pg_get_userbyid(s.srvowner))
+            || CASE WHEN obj_description(s.oid, 'pg_foreign_server') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON SERVER %I IS %L;\\n', s.srvname, obj_description(s.oid, 'pg_foreign_server'))
+                ELSE ''
+            END AS definition,
+            format('foreign_servers/%s', s.srvname) AS file_path
+        FROM pg_foreign_server s
+        INNER JOIN pg_foreign_data_wrapper w ON (s.srvfdw = w.oid)
+        WHERE s.srvname IN (SELECT * FROM UNNEST($1))
+        UNION
+        SELECT '', '', format('foreign_servers/%s', server_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($1) server_name
+        ) names
+        WHERE names.server_name NOT IN (
+            SELECT
+                s.srvname
+            FROM pg_foreign_server s
+        )
+            "
+);
+
+// User mappings are identified by user@server, with PUBLIC as the user of the mapping for all roles.
+// Their options are mostly credentials under names that depend on the wrapper, so only the user
+// name is kept and every other option is redacted
+const USER_MAPPING_DDL_QUERY: &str = concat!(
+    "
+        WITH mappings AS (
+            SELECT
+                um.*,
+                CASE WHEN um.umuser = 0 THEN 'PUBLIC' ELSE um.usename END AS user_name,
+                CASE WHEN um.umuser = 0 THEN 'PUBLIC' ELSE quote_ident(um.usename) END AS grantee
+            FROM pg_user_mappings um
+        )
+        SELECT
+            format('%s@%s', um.user_name, um.srvname) AS name,
+            ",
+    redacted_options!("um.umoptions", "opt.option_name IN ('user', 'username')"),
+    "
+            || format(E'CREATE USER MAPPING IF NOT EXISTS FOR %s SERVER %I', um.grantee, um.srvname)
+            || ",
+    options_clause!(
+        "um.umoptions",
+        "opt.option_name IN ('user', 'username')",
+        "\\n\\t",
+        ",\\n\\t",
+        "\\n"
+    ),
+    "
+            || E';\\n' AS definition,
+            format('user_mappings/%s/%s', um.srvname, um.user_name) AS file_path
+        FROM mappings um
+        WHERE format('%s@%s', um.user_name, um.srvname) IN (SELECT * FROM UNNEST($1))
+        UNION
+        SELECT '', '', format('user_mappings/%s', mapping_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($1) mapping_name
+        ) names
+        WHERE names.mapping_name NOT IN (
+            SELECT
+                format('%s@%s', um.user_name, um.srvname)
+            FROM mappings um
+        )
+            "
+);
+
+// Foreign tables are rebuilt from the catalog as pg_dump --table doesn't reliably dump them
+const FOREIGN_TABLE_DDL_QUERY: &str = concat!(
+    "
+        SELECT
+            c.relname AS name,
+            ",
+    redacted_options!("ft.ftoptions"),
+    "
+            || format(
+                E'CREATE FOREIGN TABLE IF NOT EXISTS %I.%I (\\n\\t%s\\n)\\nSERVER %I',
+                ns.nspname,
+                c.relname,
+                COALESCE((
+                    SELECT
+                        string_agg(
+                            format('%I %s', a.attname, format_type(a.atttypid, a.atttypmod))
+                            || ",
+    options_clause!("a.attfdwoptions", "", ", ", ""),
+    "
+                            || CASE WHEN a.attcollation <> t.typcollation
+                                THEN (
+                                    SELECT format(' COLLATE %I.%I', cns.nspname, col.collname)
+                                    FROM pg_collation col INNER JOIN pg_namespace cns ON (col.collnamespace = cns.oid)
+                                    WHERE col.oid = a.attcollation
+                                )
+                                ELSE ''
+                            END
+                            || CASE WHEN a.attnotnull THEN ' NOT NULL' ELSE '' END
+                            || COALESCE(' DEFAULT ' || pg_get_expr(def.adbin, def.adrelid), ''),
+                            E',\\n\\t' ORDER BY a.attnum
+                        )
+                    FROM pg_attribute a
+                    INNER JOIN pg_type t ON (a.atttypid = t.oid)
+                    LEFT JOIN pg_attrdef def ON (def.adrelid = a.attrelid AND def.adnum = a.attnum)
+                    WHERE a.attrelid = c.oid
+                    AND a.attnum > 0
+                    AND NOT a.attisdropped
+                ), ''),
+                s.srvname
+            )
+            || ",
+    options_clause!("ft.ftoptions", "\\n\\t", ",\\n\\t", "\\n"),
+    "
+            || E';\\n'
+            || CASE WHEN obj_description(c.oid, 'pg_class') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON FOREIGN TABLE %I.%I IS %L;\\n', ns.nspname, c.relname, obj_description(c.oid, 'pg_class'))
+                ELSE ''
+            END
+            || COALESCE((
+                SELECT
+                    E'\\n' || string_agg(
+                        format(E'COMMENT ON COLUMN %I.%I.%I IS %L;\\n', ns.nspname, c.relname, a.attname, col_description(c.oid, a.attnum)),
+                        '' ORDER BY a.attnum
+                    )
+                FROM pg_attribute a
+                WHERE a.attrelid = c.oid
+                AND a.attnum > 0
+                AND NOT a.attisdropped
+                AND col_description(c.oid, a.attnum) IS NOT NULL
+            ), '') AS definition,
+            format('foreign_tables/%s', c.relname) AS file_path
+        FROM pg_class c
+        INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+        INNER JOIN pg_foreign_table ft ON (ft.ftrelid = c.oid)
+        INNER JOIN pg_foreign_server s ON (ft.ftserver = s.oid)
+        WHERE ns.nspname = $1
+        AND c.relname IN (SELECT * FROM UNNEST($2))
+        UNION
+        SELECT '', '', format('foreign_tables/%s', table_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($2) table_name
+        ) names
+        WHERE names.table_name NOT IN (
+            SELECT
+                c.relname
+            FROM pg_class c INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND c.relkind = 'f'
+        )
+            "
+);
+
+pub struct ForeignDataWrapperPuller {}
+
+impl DatabaseSQLPuller for ForeignDataWrapperPuller {
+    fn get_ddl_query() -> &'static str {
+        FOREIGN_DATA_WRAPPER_DDL_QUERY
+    }
+}
+
+pub struct ForeignServerPuller {}
+
+impl DatabaseSQLPuller for ForeignServerPuller {
+    fn get_ddl_query() -> &'static str {
+        FOREIGN_SERVER_DDL_QUERY
+    }
+}
+
+pub struct UserMappingPuller {}
+
+impl DatabaseSQLPuller for UserMappingPuller {
+    fn get_ddl_query() -> &'static str {
+        USER_MAPPING_DDL_QUERY
+    }
+}
+
+pub struct ForeignTablePuller {}
+
+impl SQLPuller for ForeignTablePuller {
+    fn get_ddl_query() -> &'static str {
+        FOREIGN_TABLE_DDL_QUERY
+    }
+}
@@ -0,0 +1,115 @@
+use crate::actions::pull::pullers::SQLPuller;
+
+// Indexes that back a primary key, unique or exclusion constraint are left to the table ddl as
+// they are created by the constraint itself. The definition drops the index first so that changes
+// to an index are applied when the file is run again. Dropping the index of a partitioned table
+// also drops the indexes of its partitions, so ONLY is removed from its definition for the index
+// to be recreated on every partition
+const INDEX_DDL_QUERY: &str = "
+        SELECT
+            i.relname AS name,
+            format(
+                E'DROP INDEX IF EXISTS %I.%I;\\n\\n%s;\\n',
+                ns.nspname,
+                i.relname,
+                CASE WHEN i.relkind = 'I'
+                    THEN regexp_replace(pg_get_indexdef(i.oid), ' ON ONLY ', ' ON ')
+                    ELSE pg_get_indexdef(i.oid)
+                END
+            )
+            || CASE WHEN x.indisclustered
+                THEN format(E'\\nALTER TABLE %I.%I CLUSTER ON %I;\\n', ns.nspname, t.relname, i.relname)
+                ELSE ''
+            END
+            || CASE WHEN obj_description(i.oid, 'pg_class') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON INDEX %I.%I IS %L;\\n', ns.nspname, i.relname, obj_description(i.oid, 'pg_class'))
+                ELSE ''
+            END AS definition,
+            format('indexes/%s/%s', t.relname, i.relname) AS file_path
+        FROM pg_index x
+        INNER JOIN pg_class i ON (x.indexrelid = i.oid)
+        INNER JOIN pg_class t ON (x.indrelid = t.oid)
+        INNER JOIN pg_namespace ns ON (i.relnamespace = ns.oid)
+        WHERE ns.nspname = $1
+        AND i.relname IN (SELECT * FROM UNNEST($2))
+        AND NOT EXISTS (
+            SELECT 1
+            FROM pg_constraint con
+            WHERE con.conindid = x.indexrelid
+            AND con.contype IN ('p', 'u', 'x')
+        )
+        UNION
+        SELECT '', '', format('indexes/%s', index_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($2) index_name
+        ) names
+        WHERE names.index_name NOT IN (
+            SELECT
+                c.relname
+            FROM pg_class c INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND c.relkind IN ('i', 'I')
+        )
+            ";
+
+pub struct IndexPuller {}
+
+impl SQLPuller for IndexPuller {
+    fn get_ddl_query() -> &'static str {
+        INDEX_DDL_QUERY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{actions::pull::DDL, db_manager::DbConnection};
+    use sqlx::Executor;
+
+    #[test]
+    fn partitioned_indexes_are_recreated_on_every_partition() {
+        let db_connection = tokio_test::block_on(DbConnection::new()).unwrap();
+
+        // Everything is rolled back so that the test has no side effects
+        let (definition, num_partition_indexes) = tokio_test::block_on(async {
+            let mut transaction = db_connection.get_connection_pool().begin().await?;
+
+            for statement in [
+                "CREATE SCHEMA tusk_index_test",
+                "CREATE TABLE tusk_index_test.events (id INT, created DATE) PARTITION BY RANGE (created)",
+                "CREATE TABLE tusk_index_test.events_2024 PARTITION OF tusk_index_test.events FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')",
+                "CREATE INDEX events_id_idx ON tusk_index_test.events (id)",
+            ] {
+                sqlx::query(statement).execute(&mut transaction).await?;
+            }
+
+            let ddl = sqlx::query_as::<_, DDL>(IndexPuller::get_ddl_query())
+                .bind("tusk_index_test")
+                .bind(vec![String::from("events_id_idx")])
+                .fetch_one(&mut transaction)
+                .await?;
+
+            // Reapplying the file must leave the partition indexed
+            transaction.execute(ddl.definition.as_str()).await?;
+            let num_partition_indexes: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM pg_index WHERE indrelid = 'tusk_index_test.events_2024'::regclass",
+            )
+            .fetch_one(&mut transaction)
+            .await?;
+
+            transaction.rollback().await?;
+
+            anyhow::Ok((ddl.definition, num_partition_indexes))
+        })
+        .unwrap();
+
+        assert!(definition.contains(
+            "CREATE INDEX events_id_idx ON tusk_index_test.events USING btree (id);\n"
+        ));
+        assert!(!definition.contains(" ON ONLY "));
+        assert_eq!(num_partition_indexes, 1);
+    }
+}
@@ -0,0 +1,10 @@
+use crate::actions::pull::pullers::PgDumpPuller;
+
+// pg_dump includes the indexes of the materialized view along with its definition
+pub struct MaterializedViewPuller {}
+
+impl PgDumpPuller for MaterializedViewPuller {
+    fn pg_dump_arg_gen(schema: &str, item_name:&str) -> Vec<String> {
+        vec![String::from("--schema-only"), String::from("--no-owner"), format!("--table={}.{}", schema, item_name)]
+    }
+}
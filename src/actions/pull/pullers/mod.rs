@@ -1,10 +1,22 @@
+pub mod aggregate_puller;
+pub mod cast_puller;
 pub mod data_type_puller;
+pub mod database_puller;
+pub mod event_trigger_puller;
+pub mod foreign_data_puller;
 pub mod function_puller;
+pub mod index_puller;
+pub mod materialized_view_puller;
+pub mod security_puller;
+pub mod operator_puller;
+pub mod partition_puller;
+pub mod sequence_puller;
 pub mod table_data_puller;
 pub mod table_ddl_puller;
+pub mod trigger_puller;
 pub mod view_puller;
 
-use std::pin::Pin;
+use std::{borrow::Cow, pin::Pin};
 
 use crate::{
     actions::pull::DDL,
@@ -37,12 +49,29 @@ pub trait SQLPuller {
     }
 }
 
+// The same as SQLPuller but for items that belong to the database rather than to a schema, such as
+// extensions. Use $1 to represent the items that you are getting the ddl for
+pub trait DatabaseSQLPuller {
+    fn get_ddl_query() -> &'static str;
+
+    fn get<'conn>(pool: &'conn PgPool, items: &'conn [String]) -> Result<RowStream<'conn>> {
+        Ok(sqlx::query_as::<_, DDL>(Self::get_ddl_query())
+            .bind(items)
+            .fetch(pool))
+    }
+}
+
 #[async_trait]
 pub trait PgDumpPuller: Send + 'static {
     /// This is the function that needs to be implemented per puller. It needs to return the
     /// arguments required for pg_dump
     fn pg_dump_arg_gen(schema: &str, item_name: &str) -> Vec<String>;
 
+    // Override this to alter the ddl output by pg_dump before it is written to its file
+    fn transform_ddl(ddl: &str) -> Result<Cow<'_, str>> {
+        Ok(Cow::Borrowed(ddl))
+    }
+
     // This gets all of the ddl for the input items
     async fn get(
         schema: &str,
@@ -100,13 +129,13 @@ pub trait PgDumpPuller: Send + 'static {
 
         let mut args = vec![db_name_arg.to_owned()];
         let ddl_args = Self::pg_dump_arg_gen(&schema, &item);
-        args.extend(ddl_args.into_iter());
+        args.extend(ddl_args);
 
         let user_args = UserConfig::get_global()?
             .pull_options
             .pg_dump_additional_args
             .clone();
-        args.extend(user_args.into_iter());
+        args.extend(user_args);
 
         let command = tokio::process::Command::new(pg_bin_path)
             .args(args)
@@ -127,9 +156,9 @@ pub trait PgDumpPuller: Send + 'static {
         }
         let command_out = command.stdout;
 
-        let ddl = Self::get_ddl_from_bytes(&command_out)?;
+        let ddl = Self::transform_ddl(Self::get_ddl_from_bytes(&command_out)?)?;
 
-        tokio::fs::write(&file_path, ddl).await?;
+        tokio::fs::write(&file_path, ddl.as_bytes()).await?;
         println!("\tPulling {}", file_path.magenta());
         Ok(())
     }
@@ -0,0 +1,108 @@
+use crate::actions::pull::pullers::SQLPuller;
+
+// Operators are identified by name(left_type,right_type) using the internal type names so that the
+// names contain no spaces. The characters %, /, # and ! are percent encoded as they can't be used in
+// file names or config files. Types outside of pg_catalog are schema qualified in the definition.
+// The definition drops the operator first as there is no CREATE OR REPLACE OPERATOR
+const OPERATOR_DDL_QUERY: &str = "
+        WITH operators AS (
+            SELECT
+                o.*,
+                ns.nspname,
+                format(
+                    '%s(%s,%s)',
+                    replace(replace(replace(replace(o.oprname, '%', '%25'), '/', '%2F'), '#', '%23'), '!', '%21'),
+                    COALESCE(lt.typname, 'NONE'),
+                    rt.typname
+                ) AS item_name,
+                CASE
+                    WHEN lt.oid IS NULL THEN 'NONE'
+                    WHEN lns.nspname = 'pg_catalog' THEN format_type(lt.oid, NULL)
+                    ELSE format('%I.%I', lns.nspname, lt.typname)
+                END AS left_type,
+                CASE WHEN rns.nspname = 'pg_catalog'
+                    THEN format_type(rt.oid, NULL)
+                    ELSE format('%I.%I', rns.nspname, rt.typname)
+                END AS right_type
+            FROM pg_operator o
+            INNER JOIN pg_namespace ns ON (o.oprnamespace = ns.oid)
+            LEFT JOIN pg_type lt ON (o.oprleft = lt.oid)
+            LEFT JOIN pg_namespace lns ON (lt.typnamespace = lns.oid)
+            INNER JOIN pg_type rt ON (o.oprright = rt.oid)
+            INNER JOIN pg_namespace rns ON (rt.typnamespace = rns.oid)
+            WHERE ns.nspname = $1
+            AND NOT EXISTS (
+                SELECT 1
+                FROM pg_depend d
+                WHERE d.classid = 'pg_operator'::regclass
+                AND d.objid = o.oid
+                AND d.deptype = 'e'
+            )
+        )
+        SELECT
+            o.item_name AS name,
+            format(E'DROP OPERATOR IF EXISTS %I.%s (%s, %s);\\n\\n', o.nspname, o.oprname, o.left_type, o.right_type)
+            || format(
+                E'CREATE OPERATOR %I.%s (\\n\\t%s\\n);\\n',
+                o.nspname,
+                o.oprname,
+                array_to_string(ARRAY[
+                    'FUNCTION = ' || (
+                        SELECT format('%I.%I', n.nspname, f.proname)
+                        FROM pg_proc f INNER JOIN pg_namespace n ON (f.pronamespace = n.oid)
+                        WHERE f.oid = o.oprcode
+                    ),
+                    'LEFTARG = ' || NULLIF(o.left_type, 'NONE'),
+                    'RIGHTARG = ' || o.right_type,
+                    'COMMUTATOR = ' || (
+                        SELECT format('OPERATOR(%I.%s)', n.nspname, c.oprname)
+                        FROM pg_operator c INNER JOIN pg_namespace n ON (c.oprnamespace = n.oid)
+                        WHERE c.oid = o.oprcom
+                    ),
+                    'NEGATOR = ' || (
+                        SELECT format('OPERATOR(%I.%s)', n.nspname, neg.oprname)
+                        FROM pg_operator neg INNER JOIN pg_namespace n ON (neg.oprnamespace = n.oid)
+                        WHERE neg.oid = o.oprnegate
+                    ),
+                    'RESTRICT = ' || (
+                        SELECT format('%I.%I', n.nspname, f.proname)
+                        FROM pg_proc f INNER JOIN pg_namespace n ON (f.pronamespace = n.oid)
+                        WHERE f.oid = o.oprrest
+                    ),
+                    'JOIN = ' || (
+                        SELECT format('%I.%I', n.nspname, f.proname)
+                        FROM pg_proc f INNER JOIN pg_namespace n ON (f.pronamespace = n.oid)
+                        WHERE f.oid = o.oprjoin
+                    ),
+                    CASE WHEN o.oprcanhash THEN 'HASHES' END,
+                    CASE WHEN o.oprcanmerge THEN 'MERGES' END
+                ], E',\\n\\t')
+            )
+            || CASE WHEN obj_description(o.oid, 'pg_operator') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON OPERATOR %I.%s (%s, %s) IS %L;\\n', o.nspname, o.oprname, o.left_type, o.right_type, obj_description(o.oid, 'pg_operator'))
+                ELSE ''
+            END AS definition,
+            format('operators/%s', o.item_name) AS file_path
+        FROM operators o
+        WHERE o.item_name IN (SELECT * FROM UNNEST($2))
+        UNION
+        SELECT '', '', format('operators/%s', operator_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($2) operator_name
+        ) names
+        WHERE names.operator_name NOT IN (
+            SELECT
+                o.item_name
+            FROM operators o
+        )
+            ";
+
+pub struct OperatorPuller {}
+
+impl SQLPuller for OperatorPuller {
+    fn get_ddl_query() -> &'static str {
+        OPERATOR_DDL_QUERY
+    }
+}
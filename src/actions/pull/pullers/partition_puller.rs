@@ -0,0 +1,44 @@
+use crate::actions::pull::pullers::SQLPuller;
+
+// A summary of every partition of a partitioned table, including partitions of partitions, as one
+// CREATE TABLE ... PARTITION OF per partition. The partitions are ordered by their level within the
+// partition tree so that partitions which are themselves partitioned are created before their own
+// partitions. Tracked tables that aren't partitioned are skipped
+const PARTITION_DDL_QUERY: &str = "
+        SELECT
+            c.relname AS name,
+            format(E'-- Partitions of %I.%I\\n\\n', ns.nspname, c.relname)
+            || string_agg(
+                format(
+                    E'CREATE TABLE IF NOT EXISTS %I.%I PARTITION OF %I.%I %s%s;\\n',
+                    pns.nspname,
+                    p.relname,
+                    parent_ns.nspname,
+                    parent.relname,
+                    pg_get_expr(p.relpartbound, p.oid),
+                    CASE WHEN p.relkind = 'p' THEN ' PARTITION BY ' || pg_get_partkeydef(p.oid) ELSE '' END
+                ),
+                '' ORDER BY tree.level, pns.nspname, p.relname
+            ) AS definition,
+            format('partitions/%s', c.relname) AS file_path
+        FROM pg_class c
+        INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+        CROSS JOIN pg_partition_tree(c.oid) tree
+        INNER JOIN pg_class p ON (tree.relid = p.oid)
+        INNER JOIN pg_namespace pns ON (p.relnamespace = pns.oid)
+        INNER JOIN pg_class parent ON (tree.parentrelid = parent.oid)
+        INNER JOIN pg_namespace parent_ns ON (parent.relnamespace = parent_ns.oid)
+        WHERE ns.nspname = $1
+        AND c.relkind = 'p'
+        AND NOT c.relispartition
+        AND c.relname IN (SELECT * FROM UNNEST($2))
+        GROUP BY ns.nspname, c.relname
+            ";
+
+pub struct PartitionPuller {}
+
+impl SQLPuller for PartitionPuller {
+    fn get_ddl_query() -> &'static str {
+        PARTITION_DDL_QUERY
+    }
+}
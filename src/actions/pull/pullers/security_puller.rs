@@ -0,0 +1,371 @@
+use crate::actions::pull::pullers::SQLPuller;
+
+// Builds a security query from a CTE named objects with the columns name, object (as written in a
+// GRANT), acl, acl_type (as used by acldefault), owner, preamble, postamble and file_path. The
+// privileges are written as a REVOKE from PUBLIC followed by a GRANT per grantee so that the
+// output is deterministic. The owner's privileges are implicit and are not written
+macro_rules! security_query {
+    ($objects:expr, $security_dir:literal) => {
+        concat!(
+            $objects,
+            "
+        SELECT
+            o.name,
+            o.preamble
+            || format(E'REVOKE ALL ON %s FROM PUBLIC;\\n', o.object)
+            || COALESCE((
+                SELECT
+                    string_agg(
+                        format(
+                            E'GRANT %s ON %s TO %s%s;\\n',
+                            g.privileges,
+                            o.object,
+                            g.grantee,
+                            CASE WHEN g.is_grantable THEN ' WITH GRANT OPTION' ELSE '' END
+                        ),
+                        '' ORDER BY g.grantee, g.is_grantable
+                    )
+                FROM (
+                    SELECT
+                        CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE quote_ident(pg_get_userbyid(a.grantee)) END AS grantee,
+                        a.is_grantable,
+                        string_agg(a.privilege_type, ', ' ORDER BY a.privilege_type) AS privileges
+                    FROM aclexplode(COALESCE(o.acl, acldefault(o.acl_type, o.owner))) a
+                    WHERE a.grantee <> o.owner
+                    GROUP BY 1, 2
+                ) g
+            ), '')
+            || o.postamble AS definition,
+            o.file_path
+        FROM objects o
+        UNION
+        SELECT '', '', format('security/",
+            $security_dir,
+            "/%s', item_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($2) item_name
+        ) names
+        WHERE names.item_name NOT IN (
+            SELECT
+                o.name
+            FROM objects o
+        )
+            "
+        )
+    };
+}
+
+// Tables also carry their row level security settings, policies and column privileges. Foreign
+// tables are tracked separately from tables and don't support row level security so only their
+// privileges are written
+macro_rules! table_security_query {
+    ($relkinds:literal, $security_dir:literal) => {
+        security_query!(
+            concat!(
+                "
+        WITH objects AS (
+            SELECT
+                c.relname AS name,
+                format('TABLE %I.%I', ns.nspname, c.relname) AS object,
+                c.relacl AS acl,
+                'r'::\"char\" AS acl_type,
+                c.relowner AS owner,
+                CASE WHEN c.relkind IN ('r', 'p') THEN format(
+                    E'ALTER TABLE %I.%I %s ROW LEVEL SECURITY;\\nALTER TABLE %I.%I %s ROW LEVEL SECURITY;\\n\\n',
+                    ns.nspname,
+                    c.relname,
+                    CASE WHEN c.relrowsecurity THEN 'ENABLE' ELSE 'DISABLE' END,
+                    ns.nspname,
+                    c.relname,
+                    CASE WHEN c.relforcerowsecurity THEN 'FORCE' ELSE 'NO FORCE' END
+                )
+                || COALESCE((
+                    SELECT
+                        string_agg(
+                            format(
+                                E'DROP POLICY IF EXISTS %I ON %I.%I;\\nCREATE POLICY %I ON %I.%I AS %s FOR %s TO %s',
+                                p.policyname,
+                                p.schemaname,
+                                p.tablename,
+                                p.policyname,
+                                p.schemaname,
+                                p.tablename,
+                                p.permissive,
+                                p.cmd,
+                                array_to_string(ARRAY(
+                                    SELECT CASE WHEN r = 'public' THEN 'PUBLIC' ELSE quote_ident(r) END
+                                    FROM UNNEST(p.roles) r
+                                ), ', ')
+                            )
+                            || COALESCE(E'\\n\\tUSING (' || p.qual || ')', '')
+                            || COALESCE(E'\\n\\tWITH CHECK (' || p.with_check || ')', '')
+                            || E';\\n\\n',
+                            '' ORDER BY p.policyname
+                        )
+                    FROM pg_policies p
+                    WHERE p.schemaname = ns.nspname
+                    AND p.tablename = c.relname
+                ), '') ELSE '' END AS preamble,
+                COALESCE((
+                    SELECT
+                        string_agg(
+                            format(
+                                E'GRANT %s ON TABLE %I.%I TO %s%s;\\n',
+                                col.privileges,
+                                ns.nspname,
+                                c.relname,
+                                col.grantee,
+                                CASE WHEN col.is_grantable THEN ' WITH GRANT OPTION' ELSE '' END
+                            ),
+                            '' ORDER BY col.attnum, col.grantee, col.is_grantable
+                        )
+                    FROM (
+                        SELECT
+                            att.attnum,
+                            att.attname,
+                            CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE quote_ident(pg_get_userbyid(a.grantee)) END AS grantee,
+                            a.is_grantable,
+                            string_agg(format('%s (%I)', a.privilege_type, att.attname), ', ' ORDER BY a.privilege_type) AS privileges
+                        FROM pg_attribute att
+                        CROSS JOIN aclexplode(att.attacl) a
+                        WHERE att.attrelid = c.oid
+                        AND NOT att.attisdropped
+                        GROUP BY 1, 2, 3, 4
+                    ) col
+                ), '') AS postamble,
+                format('security/",
+                $security_dir,
+                "/%s', c.relname) AS file_path
+            FROM pg_class c
+            INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND c.relkind IN ",
+                $relkinds,
+                "
+            AND c.relname IN (SELECT * FROM UNNEST($2))
+        )"
+            ),
+            $security_dir
+        )
+    };
+}
+
+const TABLE_SECURITY_QUERY: &str = table_security_query!("('r', 'p')", "tables");
+
+const FOREIGN_TABLE_SECURITY_QUERY: &str = table_security_query!("('f')", "foreign_tables");
+
+// Materialized views are tracked separately from views so their privileges are written to their
+// own directory
+macro_rules! view_security_query {
+    ($relkind:literal, $security_dir:literal) => {
+        security_query!(
+            concat!(
+                "
+        WITH objects AS (
+            SELECT
+                c.relname AS name,
+                format('TABLE %I.%I', ns.nspname, c.relname) AS object,
+                c.relacl AS acl,
+                'r'::\"char\" AS acl_type,
+                c.relowner AS owner,
+                '' AS preamble,
+                '' AS postamble,
+                format('security/",
+                $security_dir,
+                "/%s', c.relname) AS file_path
+            FROM pg_class c
+            INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND c.relkind = '",
+                $relkind,
+                "'
+            AND c.relname IN (SELECT * FROM UNNEST($2))
+        )"
+            ),
+            $security_dir
+        )
+    };
+}
+
+const VIEW_SECURITY_QUERY: &str = view_security_query!("v", "views");
+
+const MATERIALIZED_VIEW_SECURITY_QUERY: &str =
+    view_security_query!("m", "materialized_views");
+
+const FUNCTION_SECURITY_QUERY: &str = security_query!(
+    "
+        WITH objects AS (
+            SELECT
+                p.proname AS name,
+                format('ROUTINE %I.%I(%s)', ns.nspname, p.proname, oidvectortypes(p.proargtypes)) AS object,
+                p.proacl AS acl,
+                'f'::\"char\" AS acl_type,
+                p.proowner AS owner,
+                '' AS preamble,
+                '' AS postamble,
+                format('security/functions/%I/%I(%s)', p.proname, p.proname, oidvectortypes(p.proargtypes)) AS file_path
+            FROM pg_proc p
+            INNER JOIN pg_namespace ns ON (p.pronamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND p.prokind IN ('f', 'p')
+            AND p.proname IN (SELECT * FROM UNNEST($2))
+        )",
+    "functions"
+);
+
+const DATA_TYPE_SECURITY_QUERY: &str = security_query!(
+    "
+        WITH objects AS (
+            SELECT
+                t.typname AS name,
+                format('TYPE %I.%I', ns.nspname, t.typname) AS object,
+                t.typacl AS acl,
+                'T'::\"char\" AS acl_type,
+                t.typowner AS owner,
+                '' AS preamble,
+                '' AS postamble,
+                format('security/data_types/%s', t.typname) AS file_path
+            FROM pg_type t
+            INNER JOIN pg_namespace ns ON (t.typnamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND t.typname IN (SELECT * FROM UNNEST($2))
+        )",
+    "data_types"
+);
+
+pub struct TableSecurityPuller {}
+
+impl SQLPuller for TableSecurityPuller {
+    fn get_ddl_query() -> &'static str {
+        TABLE_SECURITY_QUERY
+    }
+}
+
+pub struct ForeignTableSecurityPuller {}
+
+impl SQLPuller for ForeignTableSecurityPuller {
+    fn get_ddl_query() -> &'static str {
+        FOREIGN_TABLE_SECURITY_QUERY
+    }
+}
+
+pub struct ViewSecurityPuller {}
+
+impl SQLPuller for ViewSecurityPuller {
+    fn get_ddl_query() -> &'static str {
+        VIEW_SECURITY_QUERY
+    }
+}
+
+pub struct MaterializedViewSecurityPuller {}
+
+impl SQLPuller for MaterializedViewSecurityPuller {
+    fn get_ddl_query() -> &'static str {
+        MATERIALIZED_VIEW_SECURITY_QUERY
+    }
+}
+
+pub struct FunctionSecurityPuller {}
+
+impl SQLPuller for FunctionSecurityPuller {
+    fn get_ddl_query() -> &'static str {
+        FUNCTION_SECURITY_QUERY
+    }
+}
+
+pub struct DataTypeSecurityPuller {}
+
+impl SQLPuller for DataTypeSecurityPuller {
+    fn get_ddl_query() -> &'static str {
+        DATA_TYPE_SECURITY_QUERY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{actions::pull::DDL, db_manager::DbConnection};
+    use sqlx::Executor;
+
+    #[test]
+    fn column_privileges_are_granted_per_column() {
+        let db_connection = tokio_test::block_on(DbConnection::new()).unwrap();
+
+        // Everything is rolled back so that the test has no side effects
+        let definition = tokio_test::block_on(async {
+            let mut transaction = db_connection.get_connection_pool().begin().await?;
+
+            for statement in [
+                "CREATE SCHEMA tusk_security_test",
+                "CREATE TABLE tusk_security_test.accounts (id INT, balance INT)",
+                "CREATE ROLE tusk_security_test_role",
+                "GRANT INSERT (balance), SELECT (balance), SELECT (id) ON tusk_security_test.accounts TO tusk_security_test_role",
+            ] {
+                sqlx::query(statement).execute(&mut transaction).await?;
+            }
+
+            let ddl = sqlx::query_as::<_, DDL>(TableSecurityPuller::get_ddl_query())
+                .bind("tusk_security_test")
+                .bind(vec![String::from("accounts")])
+                .fetch_one(&mut transaction)
+                .await?;
+
+            transaction.rollback().await?;
+
+            anyhow::Ok(ddl.definition)
+        })
+        .unwrap();
+
+        assert!(definition.contains(
+            "GRANT SELECT (id) ON TABLE tusk_security_test.accounts TO tusk_security_test_role;\n"
+        ));
+        assert!(definition.contains(
+            "GRANT INSERT (balance), SELECT (balance) ON TABLE tusk_security_test.accounts TO tusk_security_test_role;\n"
+        ));
+        assert!(!definition.contains("GRANT INSERT, SELECT"));
+    }
+
+    #[test]
+    fn foreign_tables_are_pulled_without_row_level_security() {
+        let db_connection = tokio_test::block_on(DbConnection::new()).unwrap();
+
+        // Everything is rolled back so that the test has no side effects
+        let (definition, file_path) = tokio_test::block_on(async {
+            let mut transaction = db_connection.get_connection_pool().begin().await?;
+
+            for statement in [
+                "CREATE SCHEMA tusk_security_test",
+                "CREATE FOREIGN DATA WRAPPER tusk_security_test_wrapper",
+                "CREATE SERVER tusk_security_test_server FOREIGN DATA WRAPPER tusk_security_test_wrapper",
+                "CREATE FOREIGN TABLE tusk_security_test.remote_accounts (id INT) SERVER tusk_security_test_server",
+                "CREATE ROLE tusk_security_test_role",
+                "GRANT SELECT ON tusk_security_test.remote_accounts TO tusk_security_test_role",
+            ] {
+                sqlx::query(statement).execute(&mut transaction).await?;
+            }
+
+            let ddl = sqlx::query_as::<_, DDL>(ForeignTableSecurityPuller::get_ddl_query())
+                .bind("tusk_security_test")
+                .bind(vec![String::from("remote_accounts")])
+                .fetch_one(&mut transaction)
+                .await?;
+
+            // The definition must apply cleanly to the foreign table
+            transaction.execute(ddl.definition.as_str()).await?;
+
+            transaction.rollback().await?;
+
+            anyhow::Ok((ddl.definition, ddl.file_path))
+        })
+        .unwrap();
+
+        assert_eq!(file_path, "security/foreign_tables/remote_accounts");
+        assert!(!definition.contains("ROW LEVEL SECURITY"));
+        assert!(definition.contains(
+            "GRANT SELECT ON TABLE tusk_security_test.remote_accounts TO tusk_security_test_role;\n"
+        ));
+    }
+}
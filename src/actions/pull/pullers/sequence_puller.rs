@@ -0,0 +1,91 @@
+use crate::{
+    actions::pull::{
+        pullers::{RowStream, SQLPuller},
+        DDL,
+    },
+    config_file_manager::user_config::UserConfig,
+};
+use anyhow::Result;
+use sqlx::PgPool;
+
+// $3 decides whether the current value of the sequence is restored with setval. The value is only
+// known once the sequence has been used and the user has the privileges to read it
+const SEQUENCE_DDL_QUERY: &str = "
+        SELECT
+            c.relname AS name,
+            format(
+                E'CREATE SEQUENCE IF NOT EXISTS %I.%I\\n\\tAS %s\\n\\tINCREMENT BY %s\\n\\tMINVALUE %s\\n\\tMAXVALUE %s\\n\\tSTART WITH %s\\n\\tCACHE %s\\n\\t%sCYCLE;\\n',
+                ns.nspname,
+                c.relname,
+                format_type(s.seqtypid, NULL),
+                s.seqincrement,
+                s.seqmin,
+                s.seqmax,
+                s.seqstart,
+                s.seqcache,
+                CASE WHEN s.seqcycle THEN '' ELSE 'NO ' END
+            )
+            || COALESCE((
+                SELECT
+                    format(E'\\nALTER SEQUENCE %I.%I OWNED BY %I.%I.%I;\\n', ns.nspname, c.relname, tns.nspname, t.relname, a.attname)
+                FROM pg_depend d
+                INNER JOIN pg_class t ON (d.refobjid = t.oid)
+                INNER JOIN pg_namespace tns ON (t.relnamespace = tns.oid)
+                INNER JOIN pg_attribute a ON (a.attrelid = t.oid AND a.attnum = d.refobjsubid)
+                WHERE d.classid = 'pg_class'::regclass
+                AND d.refclassid = 'pg_class'::regclass
+                AND d.objid = c.oid
+                AND d.deptype = 'a'
+                LIMIT 1
+            ), '')
+            || CASE
+                WHEN $3 AND ps.last_value IS NOT NULL
+                THEN format(E'\\nSELECT pg_catalog.setval(%L, %s, true);\\n', format('%I.%I', ns.nspname, c.relname), ps.last_value)
+                ELSE ''
+            END AS definition,
+            format('sequences/%s', c.relname) AS file_path
+        FROM pg_sequence s
+        INNER JOIN pg_class c ON (s.seqrelid = c.oid)
+        INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+        LEFT JOIN pg_sequences ps ON (ps.schemaname = ns.nspname AND ps.sequencename = c.relname)
+        WHERE ns.nspname = $1
+        AND c.relname IN (SELECT * FROM UNNEST($2))
+        UNION
+        SELECT '', '', format('sequences/%s', sequence_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($2) sequence_name
+        ) names
+        WHERE names.sequence_name NOT IN (
+            SELECT
+                c.relname
+            FROM pg_class c INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND c.relkind = 'S'
+        )
+            ";
+
+pub struct SequencePuller {}
+
+impl SQLPuller for SequencePuller {
+    fn get_ddl_query() -> &'static str {
+        SEQUENCE_DDL_QUERY
+    }
+
+    fn get<'conn>(
+        pool: &'conn PgPool,
+        schema: &'conn str,
+        items: &'conn [String],
+    ) -> Result<RowStream<'conn>> {
+        let include_values = UserConfig::get_global()?
+            .pull_options
+            .include_sequence_values;
+
+        Ok(sqlx::query_as::<_, DDL>(Self::get_ddl_query())
+            .bind(schema)
+            .bind(items)
+            .bind(include_values)
+            .fetch(pool))
+    }
+}
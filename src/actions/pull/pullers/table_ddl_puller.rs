@@ -1,9 +1,204 @@
-use crate::actions::pull::pullers::PgDumpPuller;
+use std::borrow::Cow;
+
+use crate::{
+    actions::pull::pullers::PgDumpPuller, config_file_manager::user_config::UserConfig,
+};
+use anyhow::Result;
+
+// Every object in the pg_dump output starts with a comment header of the form
+// --
+// -- Name: people; Type: TABLE; Schema: public; Owner: -
+// --
+const PG_DUMP_ENTRY_HEADER: &str = "--\n-- ";
 
 pub struct TableDDLPuller {}
 
+impl TableDDLPuller {
+    // Remove the entries of the output of pg_dump for which should_remove returns true when given
+    // the name and type from the entry's header
+    fn remove_entries(ddl: &str, should_remove: impl Fn(&str, &str) -> bool) -> String {
+        let mut entry_starts: Vec<usize> = ddl
+            .match_indices(PG_DUMP_ENTRY_HEADER)
+            .map(|(idx, _)| idx)
+            .collect();
+        entry_starts.push(ddl.len());
+
+        let mut result = String::from(&ddl[..entry_starts[0]]);
+        for entry in entry_starts.windows(2).map(|bounds| &ddl[bounds[0]..bounds[1]]) {
+            let header = entry[PG_DUMP_ENTRY_HEADER.len()..]
+                .lines()
+                .next()
+                .unwrap_or_default();
+            let mut fields = header.split("; ");
+            let name = fields
+                .next()
+                .and_then(|field| field.strip_prefix("Name: "))
+                .unwrap_or_default();
+            let entry_type = fields
+                .find_map(|field| field.strip_prefix("Type: "))
+                .unwrap_or_default();
+
+            if !should_remove(name, entry_type) {
+                result.push_str(entry);
+            }
+        }
+
+        result
+    }
+
+    // Remove the indexes, and the comments and partition attachments of the indexes, from the
+    // output of pg_dump. Indexes that back constraints are part of the constraint and are kept
+    fn remove_index_entries(ddl: &str) -> String {
+        Self::remove_entries(ddl, |name, entry_type| {
+            matches!(entry_type, "INDEX" | "INDEX ATTACH")
+                || (entry_type == "COMMENT" && name.starts_with("INDEX "))
+        })
+    }
+
+    // Remove the triggers, and the comments of the triggers, from the output of pg_dump
+    fn remove_trigger_entries(ddl: &str) -> String {
+        Self::remove_entries(ddl, |name, entry_type| {
+            entry_type == "TRIGGER" || (entry_type == "COMMENT" && name.starts_with("TRIGGER "))
+        })
+    }
+}
+
 impl PgDumpPuller for TableDDLPuller {
     fn pg_dump_arg_gen(schema: &str, item_name:&str) -> Vec<String> {
         vec![String::from("--schema-only"), String::from("--no-owner"), format!("--table={}.{}", schema, item_name)]
     }
+
+    fn transform_ddl(ddl: &str) -> Result<Cow<'_, str>> {
+        let pull_options = &UserConfig::get_global()?.pull_options;
+
+        let mut ddl = Cow::Borrowed(ddl);
+        if pull_options.exclude_indexes_from_table_ddl {
+            ddl = Cow::Owned(Self::remove_index_entries(&ddl));
+        }
+        if pull_options.exclude_triggers_from_table_ddl {
+            ddl = Cow::Owned(Self::remove_trigger_entries(&ddl));
+        }
+
+        Ok(ddl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_index_entries_works() {
+        let ddl = "SET statement_timeout = 0;
+
+--
+-- Name: people; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.people (
+    id integer NOT NULL,
+    name text
+);
+
+
+--
+-- Name: people people_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.people
+    ADD CONSTRAINT people_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: people_name_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX people_name_idx ON public.people USING btree (name);
+
+ALTER TABLE public.people CLUSTER ON people_name_idx;
+
+
+--
+-- Name: INDEX people_name_idx; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON INDEX public.people_name_idx IS 'names';
+
+
+--
+-- Name: TABLE people; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.people IS 'people';
+
+
+--
+-- Name: people_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX people_id_idx ON public.people USING btree (id);
+
+
+--
+-- PostgreSQL database dump complete
+--
+";
+
+        let result = TableDDLPuller::remove_index_entries(ddl);
+
+        assert!(!result.contains("people_name_idx"));
+        assert!(!result.contains("people_id_idx"));
+        assert!(result.contains("ADD CONSTRAINT people_pkey PRIMARY KEY (id);"));
+        assert!(result.contains("COMMENT ON TABLE public.people IS 'people';"));
+        assert!(result.ends_with("-- PostgreSQL database dump complete\n--\n"));
+        assert_eq!(TableDDLPuller::remove_index_entries("SET a = 1;\n"), "SET a = 1;\n");
+    }
+
+    #[test]
+    fn remove_trigger_entries_works() {
+        let ddl = "SET statement_timeout = 0;
+
+--
+-- Name: people; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.people (
+    id integer NOT NULL,
+    name text
+);
+
+
+--
+-- Name: people_name_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX people_name_idx ON public.people USING btree (name);
+
+
+--
+-- Name: people audit_people; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit_people AFTER INSERT ON public.people FOR EACH ROW EXECUTE FUNCTION public.audit();
+
+
+--
+-- Name: TRIGGER audit_people ON people; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TRIGGER audit_people ON public.people IS 'audits';
+
+
+--
+-- PostgreSQL database dump complete
+--
+";
+
+        let result = TableDDLPuller::remove_trigger_entries(ddl);
+
+        assert!(!result.contains("audit_people"));
+        assert!(result.contains("CREATE INDEX people_name_idx ON public.people USING btree (name);"));
+        assert!(result.contains("CREATE TABLE public.people ("));
+        assert!(result.ends_with("-- PostgreSQL database dump complete\n--\n"));
+    }
 }
@@ -0,0 +1,51 @@
+use crate::actions::pull::pullers::SQLPuller;
+
+// Triggers are identified by table.trigger because trigger names are only unique per table. The
+// definition drops the trigger first so that it can be reapplied over the copy that pg_dump
+// includes in the table ddl
+const TRIGGER_DDL_QUERY: &str = "
+        SELECT
+            format('%s.%s', c.relname, t.tgname) AS name,
+            format(E'DROP TRIGGER IF EXISTS %I ON %I.%I;\\n\\n%s;\\n', t.tgname, ns.nspname, c.relname, pg_get_triggerdef(t.oid))
+            || CASE t.tgenabled
+                WHEN 'D' THEN format(E'\\nALTER TABLE %I.%I DISABLE TRIGGER %I;\\n', ns.nspname, c.relname, t.tgname)
+                WHEN 'R' THEN format(E'\\nALTER TABLE %I.%I ENABLE REPLICA TRIGGER %I;\\n', ns.nspname, c.relname, t.tgname)
+                WHEN 'A' THEN format(E'\\nALTER TABLE %I.%I ENABLE ALWAYS TRIGGER %I;\\n', ns.nspname, c.relname, t.tgname)
+                ELSE ''
+            END
+            || CASE WHEN obj_description(t.oid, 'pg_trigger') IS NOT NULL
+                THEN format(E'\\nCOMMENT ON TRIGGER %I ON %I.%I IS %L;\\n', t.tgname, ns.nspname, c.relname, obj_description(t.oid, 'pg_trigger'))
+                ELSE ''
+            END AS definition,
+            format('triggers/%s/%s', c.relname, t.tgname) AS file_path
+        FROM pg_trigger t
+        INNER JOIN pg_class c ON (t.tgrelid = c.oid)
+        INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+        WHERE ns.nspname = $1
+        AND NOT t.tgisinternal -- Constraint triggers created for foreign keys are part of the table
+        AND format('%s.%s', c.relname, t.tgname) IN (SELECT * FROM UNNEST($2))
+        UNION
+        SELECT '', '', format('triggers/%s', trigger_name)
+        FROM (
+            SELECT
+                *
+            FROM UNNEST($2) trigger_name
+        ) names
+        WHERE names.trigger_name NOT IN (
+            SELECT
+                format('%s.%s', c.relname, t.tgname)
+            FROM pg_trigger t
+            INNER JOIN pg_class c ON (t.tgrelid = c.oid)
+            INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND NOT t.tgisinternal
+        )
+            ";
+
+pub struct TriggerPuller {}
+
+impl SQLPuller for TriggerPuller {
+    fn get_ddl_query() -> &'static str {
+        TRIGGER_DDL_QUERY
+    }
+}
@@ -1,18 +1,23 @@
 use std::collections::HashMap;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Args;
 use colored::Colorize;
-use sqlx::{Acquire, Postgres};
+use sqlx::{Acquire, Executor, Postgres};
 use walkdir;
 
 use crate::{
-    actions::{init::SCHEMA_CONFIG_LOCATION, unit_test::UnitTest},
+    actions::{
+        doc::doc_parser::{get_doc_comment_parser, DocCommentParser, FunctionDocParser},
+        function_signature::quote_ident,
+        init::SCHEMA_CONFIG_LOCATION,
+        unit_test::{test_runner::TestFilter, UnitTest},
+    },
     config_file_manager::{
         ddl_config::{
             get_commented_file_contents, get_matching_file_contents, get_uncommented_file_contents,
         },
-        user_config::UserConfig,
+        user_config::{DocCommentStyle, UserConfig},
     },
     db_manager,
 };
@@ -21,7 +26,7 @@ use crate::{
 pub struct Push {
     /// The functions to push to the database. Specify the schema as my_schema.func or my_schema.% to
     /// push all of the functions within my_schema
-    #[clap(num_args = 0.., index=1, required_unless_present="all")]
+    #[clap(num_args = 0.., index=1, required_unless_present_any(["all", "materialized_views"]))]
     // This is how you allow it to be a
     // positional argument rather than a flagged argument
     functions: Vec<String>,
@@ -45,6 +50,16 @@ pub struct Push {
     #[arg(long)]
     confirm: bool,
 
+    /// Set the COMMENT ON of each pushed function to its doc comment, so that the docs show in psql
+    /// and other clients
+    #[arg(long)]
+    sync_comments: bool,
+
+    /// Recreate and refresh the specified materialized views, or those that start with the input
+    /// pattern, whose local definition differs from the database
+    #[arg(long, num_args(0..))]
+    materialized_views: Option<Vec<String>>,
+
     #[clap(skip)]
     user_config_confirm_before_push: bool,
 }
@@ -92,11 +107,43 @@ impl Push {
         Ok(func_paths)
     }
 
+    // Build the statement that sets the comment of the function to its doc comment, or None if it
+    // doesn't have a doc comment
+    fn get_comment_statement(
+        schema: &str,
+        func_name: &str,
+        func_path: &str,
+        file_contents: &str,
+        doc_parser: &dyn DocCommentParser,
+    ) -> Result<Option<String>> {
+        // The file name is the name of the function followed by its input argument types
+        let overload_name = std::path::Path::new(func_path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .context("The file paths provided should be valid UTF-8 Characters")?;
+
+        let doc_comment = match doc_parser.find_doc_comment(overload_name, file_contents)? {
+            Some(doc_comment) => doc_comment,
+            None => return Ok(None),
+        };
+        let function_info =
+            FunctionDocParser::from_doc_comment(schema, func_name, file_contents, &doc_comment)?;
+
+        Ok(Some(format!(
+            "COMMENT ON ROUTINE {}.{} IS '{}'",
+            quote_ident(schema),
+            overload_name,
+            function_info.to_comment_text().replace('\'', "''")
+        )))
+    }
+
     async fn push_func<'c, C>(
         &self,
         conn: C,
+        schema: &str,
         func_name: &str,
         func_paths: &Vec<String>,
+        doc_parser: Option<&dyn DocCommentParser>,
     ) -> Result<()>
     where
         C: Acquire<'c, Database = Postgres>,
@@ -123,7 +170,271 @@ impl Push {
                     return Err(anyhow::anyhow!("All functions have been rolled back. Please fix the error within the function defined at: \n\t'{func_path}'"));
                 }
             };
+
+            let doc_parser = match doc_parser {
+                Some(doc_parser) => doc_parser,
+                None => continue,
+            };
+            let comment_statement = Self::get_comment_statement(
+                schema,
+                func_name,
+                func_path,
+                &file_contents,
+                doc_parser,
+            )
+            .context(format!("Could not parse the doc comment of {}", func_path))?;
+
+            match comment_statement {
+                Some(comment_statement) => {
+                    if let Err(e) = sqlx::query(&comment_statement).execute(&mut *conn).await {
+                        println!("\t\t{}: Comment not synced", "Failed".red());
+                        println!("\t\t{}", db_manager::error_handling::get_db_error(e));
+                        return Err(anyhow::anyhow!("All functions have been rolled back. Please fix the doc comment within the function defined at: \n\t'{func_path}'"));
+                    }
+                    println!("\t\t{}", "Comment Synced".green());
+                }
+                None => println!("\t\t{}: No doc comment to sync", "Warning".yellow()),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Get the paths of all locally defined materialized views within the schema keyed by their name
+    fn get_local_materialized_views(&self, schema: &str) -> Result<HashMap<String, String>> {
+        let materialized_view_dir = format!("./schemas/{}/materialized_views", schema);
+
+        let mut materialized_views = HashMap::new();
+        for entry in walkdir::WalkDir::new(materialized_view_dir)
+            .min_depth(1)
+            .max_depth(1)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+        {
+            let file_path = entry.path();
+            if file_path.extension().and_then(|ext| ext.to_str()) != Some("sql") {
+                continue;
+            }
+
+            let name = file_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .context("The file paths provided should be valid UTF-8 Characters")?;
+            let path = file_path
+                .to_str()
+                .context("The file paths provided should be valid UTF-8 Characters")?;
+            materialized_views.insert(name.to_owned(), path.to_owned());
+        }
+
+        Ok(materialized_views)
+    }
+
+    // Get the name and path of each local materialized view that is uncommented in the config file
+    // and matches the patterns, or all of them when there are no patterns
+    fn get_materialized_views_to_push(
+        &self,
+        schema: &str,
+        patterns: &[String],
+    ) -> Result<Vec<(String, String)>> {
+        let commented_materialized_views = get_commented_file_contents(&format!(
+            "./.tusk/config/schemas/{}/materialized_views_to_include.conf",
+            schema
+        ))
+        .unwrap_or_default();
+
+        let local_materialized_views: HashMap<String, String> = self
+            .get_local_materialized_views(schema)?
+            .into_iter()
+            .filter(|(name, _)| !commented_materialized_views.contains(name))
+            .collect();
+
+        let mut names: Vec<&String> = match patterns.is_empty() {
+            true => local_materialized_views.keys().collect(),
+            false => get_matching_file_contents(
+                local_materialized_views.keys(),
+                patterns,
+                Some(schema),
+            )?,
+        };
+        names.sort();
+
+        Ok(names
+            .into_iter()
+            .map(|name| (name.clone(), local_materialized_views[name].clone()))
+            .collect())
+    }
+
+    // The definition of the materialized view and its indexes as deparsed by the database, so that
+    // the local and database definitions can be compared regardless of formatting
+    async fn get_materialized_view_definition(
+        conn: &mut sqlx::PgConnection,
+        schema: &str,
+        name: &str,
+    ) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar(
+            "
+            SELECT
+                pg_get_viewdef(c.oid)
+                || COALESCE((
+                    SELECT string_agg(pg_get_indexdef(i.indexrelid), E'\n' ORDER BY pg_get_indexdef(i.indexrelid))
+                    FROM pg_index i
+                    WHERE i.indrelid = c.oid
+                ), '')
+            FROM pg_class c
+            INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND c.relname = $2
+            AND c.relkind = 'm'
+            ",
+        )
+        .bind(schema)
+        .bind(name)
+        .fetch_optional(&mut *conn)
+        .await?)
+    }
+
+    // The statements that revoke the privileges on the materialized view, and the statements that
+    // restore its owner and privileges. Pulled files don't contain the owner or privileges so they
+    // are captured before the view is dropped and restored onto the recreated view. The owner's
+    // privileges are implicit and are left alone
+    async fn get_materialized_view_security(
+        conn: &mut sqlx::PgConnection,
+        schema: &str,
+        name: &str,
+    ) -> Result<Option<(String, String)>> {
+        Ok(sqlx::query_as(
+            "
+            WITH privileges AS (
+                SELECT
+                    CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE quote_ident(pg_get_userbyid(a.grantee)) END AS grantee,
+                    a.privilege_type,
+                    a.is_grantable
+                FROM pg_class c
+                INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+                CROSS JOIN aclexplode(c.relacl) a
+                WHERE ns.nspname = $1
+                AND c.relname = $2
+                AND c.relkind = 'm'
+                AND a.grantee <> c.relowner
+            )
+            SELECT
+                COALESCE((
+                    SELECT string_agg(DISTINCT format(E'REVOKE ALL ON TABLE %I.%I FROM %s;\\n', $1, $2, p.grantee), '')
+                    FROM privileges p
+                ), ''),
+                format(E'ALTER MATERIALIZED VIEW %I.%I OWNER TO %I;\\n', $1, $2, pg_get_userbyid(c.relowner))
+                || COALESCE((
+                    SELECT string_agg(
+                        format(
+                            E'GRANT %s ON TABLE %I.%I TO %s%s;\\n',
+                            p.privilege_type,
+                            $1,
+                            $2,
+                            p.grantee,
+                            CASE WHEN p.is_grantable THEN ' WITH GRANT OPTION' ELSE '' END
+                        ),
+                        '' ORDER BY p.grantee, p.privilege_type
+                    )
+                    FROM privileges p
+                ), '')
+            FROM pg_class c
+            INNER JOIN pg_namespace ns ON (c.relnamespace = ns.oid)
+            WHERE ns.nspname = $1
+            AND c.relname = $2
+            AND c.relkind = 'm'
+            ",
+        )
+        .bind(schema)
+        .bind(name)
+        .fetch_optional(&mut *conn)
+        .await?)
+    }
+
+    // Recreate the materialized view from its file and refresh it. The view is recreated within a
+    // savepoint which is rolled back if the definition turns out to be unchanged, so that an
+    // unchanged view is never refreshed
+    async fn push_materialized_view<'c, C>(
+        &self,
+        conn: C,
+        schema: &str,
+        name: &str,
+        file_path: &str,
+    ) -> Result<()>
+    where
+        C: Acquire<'c, Database = Postgres>,
+    {
+        let mut conn = conn.acquire().await?;
+        let file_contents = std::fs::read_to_string(file_path)?;
+        let qualified_name = format!("{}.{}", quote_ident(schema), quote_ident(name));
+
+        let old_definition = Self::get_materialized_view_definition(&mut conn, schema, name).await?;
+        let old_security = Self::get_materialized_view_security(&mut conn, schema, name).await?;
+
+        let mut savepoint = Acquire::begin(&mut *conn).await?;
+        if let Err(e) = savepoint
+            .execute(format!("DROP MATERIALIZED VIEW IF EXISTS {};", qualified_name).as_str())
+            .await
+        {
+            println!("\t{}: {} {}", name.bold().magenta(), file_path, "Failed".red());
+            let is_depended_on = matches!(
+                &e,
+                sqlx::Error::Database(db_error) if db_error.code().as_deref() == Some("2BP01")
+            );
+            println!("\t\t{}", db_manager::error_handling::get_db_error(e));
+            if is_depended_on {
+                return Err(anyhow::anyhow!("All changes have been rolled back. The materialized view {qualified_name} could not be dropped as other objects depend on it. Please drop the dependent objects listed above, push the materialized view, then recreate them"));
+            }
+            return Err(anyhow::anyhow!("All changes have been rolled back. The materialized view {qualified_name} could not be dropped"));
+        }
+        if let Err(e) = savepoint.execute(file_contents.as_str()).await {
+            println!("\t{}: {} {}", name.bold().magenta(), file_path, "Failed".red());
+            println!("\t\t{}", db_manager::error_handling::get_db_error(e));
+            return Err(anyhow::anyhow!("All changes have been rolled back. Please fix the error within the materialized view defined at: \n\t'{file_path}'"));
+        }
+        // pg_dump output changes session settings such as the search_path so reset them
+        savepoint.execute("RESET ALL;").await?;
+
+        if let Some((_, restore_security)) = old_security {
+            let new_security =
+                Self::get_materialized_view_security(&mut savepoint, schema, name).await?;
+            let Some((revoke_security, _)) = new_security else {
+                println!("\t{}: {} {}", name.bold().magenta(), file_path, "Failed".red());
+                return Err(anyhow::anyhow!("All changes have been rolled back. The file defined at: \n\t'{file_path}' doesn't create the materialized view {qualified_name}"));
+            };
+            if let Err(e) = savepoint
+                .execute(format!("{}{}", revoke_security, restore_security).as_str())
+                .await
+            {
+                println!("\t{}: {} {}", name.bold().magenta(), file_path, "Failed".red());
+                println!("\t\t{}", db_manager::error_handling::get_db_error(e));
+                return Err(anyhow::anyhow!("All changes have been rolled back. The owner and privileges of the materialized view {qualified_name} could not be restored"));
+            }
+        }
+
+        let new_definition =
+            Self::get_materialized_view_definition(&mut savepoint, schema, name).await?;
+        if new_definition == old_definition {
+            savepoint.rollback().await?;
+            println!("\t{}: {} {}", name.bold().magenta(), file_path, "Unchanged".yellow());
+            return Ok(());
+        }
+        savepoint.commit().await?;
+
+        if let Err(e) = conn
+            .execute(format!("REFRESH MATERIALIZED VIEW {};", qualified_name).as_str())
+            .await
+        {
+            println!("\t{}: {} {}", name.bold().magenta(), file_path, "Failed".red());
+            println!("\t\t{}", db_manager::error_handling::get_db_error(e));
+            return Err(anyhow::anyhow!("All changes have been rolled back. The materialized view defined at: \n\t'{file_path}' could not be refreshed"));
         }
+        println!(
+            "\t{}: {} {}",
+            name.bold().magenta(),
+            file_path,
+            "Recreated and Refreshed".green()
+        );
 
         Ok(())
     }
@@ -138,6 +449,13 @@ impl Push {
         self.user_config_confirm_before_push =
             UserConfig::get_global()?.push_options.confirm_before_push;
 
+        let sync_comments =
+            self.sync_comments || UserConfig::get_global()?.push_options.sync_doc_comments;
+        let comment_style = UserConfig::get_global()?.doc_options.comment_style;
+        if sync_comments && comment_style == DocCommentStyle::CommentOn {
+            anyhow::bail!("The doc comments are already stored as COMMENT ON FUNCTION when the comment_style is comment_on, so they can't be synced");
+        }
+
         println!("\nBeginning Push:");
 
         for schema in schemas {
@@ -180,9 +498,41 @@ impl Push {
             {
                 anyhow::bail!("The items were rejected by the user. Please filter appropriately on the next run")
             }
+            let doc_parser = match sync_comments {
+                true => Some(get_doc_comment_parser(comment_style, &schema).await?),
+                false => None,
+            };
             for (func_name, func_paths) in function_path_map.iter() {
-                self.push_func(&mut *transaction, func_name, func_paths)
-                    .await?;
+                self.push_func(
+                    &mut *transaction,
+                    &schema,
+                    func_name,
+                    func_paths,
+                    doc_parser.as_deref(),
+                )
+                .await?;
+            }
+
+            // Materialized views are only pushed when they are explicitly requested
+            if let Some(patterns) = &self.materialized_views {
+                let materialized_views = self.get_materialized_views_to_push(&schema, patterns)?;
+
+                if !materialized_views.is_empty() {
+                    println!("\nBeginning {} schema materialized view push:", schema);
+                }
+                if (self.user_config_confirm_before_push || self.confirm)
+                    && !materialized_views.is_empty()
+                    && !UserConfig::user_confirmed(
+                        &schema,
+                        materialized_views.iter().map(|(name, _)| name),
+                    )?
+                {
+                    anyhow::bail!("The items were rejected by the user. Please filter appropriately on the next run")
+                }
+                for (name, file_path) in materialized_views.iter() {
+                    self.push_materialized_view(&mut *transaction, &schema, name, file_path)
+                        .await?;
+                }
             }
         }
 
@@ -190,8 +540,14 @@ impl Push {
 
         if should_unit_test && !self.no_test {
             // Run the unit tests
-            let test_results =
-                UnitTest::run_unit_tests(&mut *transaction, &self.functions, self.all).await?;
+            let test_results = UnitTest::run_unit_tests(
+                &mut *transaction,
+                &self.functions,
+                self.all,
+                &TestFilter::default(),
+                None,
+            )
+            .await?;
             if test_results.num_failed != 0 {
                 println!("{}: Due to unit test failure, all functions have been rolled back to their original state.", "Error".red());
                 transaction.rollback().await?;
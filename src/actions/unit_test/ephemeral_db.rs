@@ -0,0 +1,369 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use sqlx::{postgres::PgPoolOptions, Connection, Executor, PgConnection, PgPool};
+
+use crate::{
+    actions::{function_signature::quote_ident, init::DATABASE_DDL_LOCATION},
+    db_manager::{self, error_handling::get_db_error},
+};
+
+const MAX_EPHEMERAL_DB_CONNECTIONS: u32 = 5;
+
+// The directories of pulled DDL within a schema in the order that they must be applied to the
+// database. Files that fail are retried in later passes so that dependencies between files of the
+// same type (eg. a table referencing another table) can still be resolved
+pub const DDL_APPLY_ORDER: [&str; 16] = [
+    "data_types",
+    "sequences",
+    "table_ddl",
+    "partitions",
+    "table_data",
+    "foreign_tables",
+    "indexes",
+    "views",
+    "functions",
+    "aggregates",
+    "operators",
+    "casts",
+    "materialized_views",
+    "triggers",
+    "event_triggers",
+    "security",
+];
+
+// The directories of pulled DDL within ./database that are applied after the schema definitions,
+// in the order that they must be applied
+const DATABASE_APPLY_ORDER: [&str; 4] = [
+    "extensions",
+    "foreign_data_wrappers",
+    "foreign_servers",
+    "user_mappings",
+];
+
+// A temporary database built from the contents of the repo which is dropped once it is finished
+// with
+pub struct EphemeralDb {
+    name: String,
+    connection_string: String,
+}
+
+impl EphemeralDb {
+    pub async fn create(connection: &db_manager::DbConnection) -> Result<Self> {
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        let name = format!("tusk_ephemeral_{}_{}", created_at, std::process::id());
+
+        connection
+            .get_connection_pool()
+            .execute(format!("CREATE DATABASE {};", quote_ident(&name)).as_str())
+            .await?;
+        println!("\n{}: Temporary database {}", "Created".green(), name.magenta());
+
+        Ok(Self {
+            connection_string: connection.get_connection_string_for_db(&name),
+            name,
+        })
+    }
+
+    pub async fn connect(&self) -> Result<PgPool> {
+        Ok(PgPoolOptions::new()
+            .max_connections(MAX_EPHEMERAL_DB_CONNECTIONS)
+            .connect(&self.connection_string)
+            .await?)
+    }
+
+    // Get the pulled definitions of the schemas followed by the extensions and foreign data objects.
+    // Database settings are left out as they name the database they were pulled from
+    pub fn get_database_files(database_root: &Path, schemas: &[String]) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = schemas
+            .iter()
+            .map(|schema| database_root.join("schemas").join(format!("{}.sql", schema)))
+            .filter(|path| path.exists())
+            .collect();
+
+        for database_dir in DATABASE_APPLY_ORDER {
+            let mut dir_files: Vec<PathBuf> = walkdir::WalkDir::new(database_root.join(database_dir))
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|e| e.file_type().is_file())
+                .map(|e| e.into_path())
+                .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("sql"))
+                .collect();
+            dir_files.sort();
+            files.extend(dir_files);
+        }
+
+        files
+    }
+
+    // Get the pulled DDL files for the schema in the order they should be applied. The first stage
+    // contains the schema definition itself from before schemas were pulled into the database
+    // directory
+    pub fn get_ddl_files(schemas_root: &Path, schema: &str) -> Vec<Vec<PathBuf>> {
+        let schema_dir = schemas_root.join(schema);
+
+        let mut stages = vec![];
+        let schema_def = schema_dir.join(format!("{}.sql", schema));
+        stages.push(if schema_def.exists() {
+            vec![schema_def]
+        } else {
+            vec![]
+        });
+
+        for ddl_dir in DDL_APPLY_ORDER {
+            let mut files: Vec<PathBuf> = walkdir::WalkDir::new(schema_dir.join(ddl_dir))
+                .sort_by_file_name()
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|e| e.file_type().is_file())
+                .map(|e| e.into_path())
+                .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("sql"))
+                .collect();
+            files.sort();
+            stages.push(files);
+        }
+
+        stages
+    }
+
+    async fn apply_file(conn: &mut PgConnection, file_path: &Path) -> Result<Option<String>> {
+        let file_contents = tokio::fs::read_to_string(file_path).await?;
+
+        let mut transaction = conn.begin().await?;
+        let result = match transaction.execute(file_contents.as_str()).await {
+            Ok(_) => {
+                transaction.commit().await?;
+                None
+            }
+            Err(e) => {
+                transaction.rollback().await?;
+                Some(get_db_error(e))
+            }
+        };
+
+        // pg_dump output changes session settings such as the search_path so reset them
+        conn.execute("RESET ALL;").await?;
+
+        Ok(result)
+    }
+
+    // Apply all of the pulled DDL of the given schemas to the temporary database
+    pub async fn apply_repository(&self, schemas: &[String]) -> Result<()> {
+        println!("\nBuilding temporary database {}:", self.name.magenta());
+
+        let mut conn = PgConnection::connect(&self.connection_string).await?;
+
+        let mut stages: Vec<Vec<PathBuf>> = vec![];
+        for schema in schemas {
+            conn.execute(format!("CREATE SCHEMA IF NOT EXISTS {};", quote_ident(schema)).as_str())
+                .await?;
+
+            for (idx, files) in Self::get_ddl_files(Path::new("./schemas"), schema)
+                .into_iter()
+                .enumerate()
+            {
+                if stages.len() <= idx {
+                    stages.push(vec![]);
+                }
+                stages[idx].extend(files);
+            }
+        }
+
+        // The schemas and extensions are needed before anything within the schemas
+        stages.insert(
+            0,
+            Self::get_database_files(Path::new(DATABASE_DDL_LOCATION), schemas),
+        );
+
+        let mut pending: Vec<(PathBuf, String)> = vec![];
+        for stage in stages {
+            pending.extend(stage.into_iter().map(|path| (path, String::new())));
+
+            // Keep retrying the failed files until no more progress can be made
+            loop {
+                let num_pending = pending.len();
+                let mut still_pending = vec![];
+                for (file_path, _) in pending {
+                    match Self::apply_file(&mut conn, &file_path).await? {
+                        None => println!("\tApplied {}", file_path.display().to_string().magenta()),
+                        Some(error_message) => still_pending.push((file_path, error_message)),
+                    }
+                }
+
+                pending = still_pending;
+                if pending.is_empty() || pending.len() == num_pending {
+                    break;
+                }
+            }
+        }
+
+        conn.close().await?;
+
+        if !pending.is_empty() {
+            for (file_path, error_message) in &pending {
+                println!(
+                    "\t{}: {}",
+                    file_path.display().to_string().magenta(),
+                    "Failed".red()
+                );
+                println!("\t\t{}", error_message.replace('\n', "\n\t\t"));
+            }
+            bail!(
+                "{} pulled files could not be applied to the temporary database",
+                pending.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    // Drop the temporary database, terminating any connections that are still open to it
+    pub async fn drop_database(self, admin_pool: &PgPool) -> Result<()> {
+        admin_pool
+            .execute(
+                format!(
+                    "DROP DATABASE IF EXISTS {} WITH (FORCE);",
+                    quote_ident(&self.name)
+                )
+                .as_str(),
+            )
+            .await?;
+        println!("\n{}: Temporary database {}", "Dropped".yellow(), self.name.magenta());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir_in;
+
+    #[test]
+    fn get_database_files_works() {
+        let temp_test_dir =
+            tempdir_in(".").expect("Temporary Directory should not fail to be created");
+
+        for file in [
+            "schemas/public.sql",
+            "schemas/untracked.sql",
+            "extensions/pgcrypto.sql",
+            "extensions/citext.sql",
+            "settings/search_path.sql",
+            "foreign_data_wrappers/a_wrapper.sql",
+            "foreign_servers/a_server.sql",
+            "user_mappings/a_server/PUBLIC.sql",
+            "user_mappings/a_server/postgres.sql",
+        ] {
+            let file_path = temp_test_dir.path().join(file);
+            std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            std::fs::write(file_path, "").unwrap();
+        }
+
+        let files: Vec<String> = EphemeralDb::get_database_files(
+            temp_test_dir.path(),
+            &[String::from("public"), String::from("missing")],
+        )
+        .into_iter()
+        .map(|file| {
+            file.strip_prefix(temp_test_dir.path())
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_owned()
+        })
+        .collect();
+
+        assert_eq!(
+            files,
+            vec![
+                "schemas/public.sql",
+                "extensions/citext.sql",
+                "extensions/pgcrypto.sql",
+                "foreign_data_wrappers/a_wrapper.sql",
+                "foreign_servers/a_server.sql",
+                "user_mappings/a_server/PUBLIC.sql",
+                "user_mappings/a_server/postgres.sql"
+            ]
+        );
+    }
+
+    #[test]
+    fn get_ddl_files_works() {
+        let temp_test_dir =
+            tempdir_in(".").expect("Temporary Directory should not fail to be created");
+        let schema_dir = temp_test_dir.path().join("public");
+
+        for file in [
+            "public.sql",
+            "functions/b_func/b_func(text).sql",
+            "functions/a_func/a_func().sql",
+            "functions/a_func/unit_tests/test.yaml",
+            "views/a_view.sql",
+            "materialized_views/a_materialized_view.sql",
+            "table_ddl/a_table.sql",
+            "partitions/a_table.sql",
+            "foreign_tables/a_foreign_table.sql",
+            "data_types/a_type.sql",
+            "sequences/a_sequence.sql",
+            "indexes/a_table/an_index.sql",
+            "triggers/a_table/a_trigger.sql",
+            "aggregates/an_agg/an_agg(integer).sql",
+            "operators/===(int4,int4).sql",
+            "casts/a_type_to_text.sql",
+            "event_triggers/an_event_trigger.sql",
+            "security/tables/a_table.sql",
+            "security/functions/a_func/a_func().sql",
+        ] {
+            let file_path = schema_dir.join(file);
+            std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            std::fs::write(file_path, "").unwrap();
+        }
+
+        let stages = EphemeralDb::get_ddl_files(temp_test_dir.path(), "public");
+        let stages: Vec<Vec<String>> = stages
+            .into_iter()
+            .map(|files| {
+                files
+                    .into_iter()
+                    .map(|file| {
+                        file.strip_prefix(&schema_dir)
+                            .unwrap()
+                            .to_str()
+                            .unwrap()
+                            .to_owned()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(
+            stages,
+            vec![
+                vec!["public.sql"],
+                vec!["data_types/a_type.sql"],
+                vec!["sequences/a_sequence.sql"],
+                vec!["table_ddl/a_table.sql"],
+                vec!["partitions/a_table.sql"],
+                vec![],
+                vec!["foreign_tables/a_foreign_table.sql"],
+                vec!["indexes/a_table/an_index.sql"],
+                vec!["views/a_view.sql"],
+                vec!["functions/a_func/a_func().sql", "functions/b_func/b_func(text).sql"],
+                vec!["aggregates/an_agg/an_agg(integer).sql"],
+                vec!["operators/===(int4,int4).sql"],
+                vec!["casts/a_type_to_text.sql"],
+                vec!["materialized_views/a_materialized_view.sql"],
+                vec!["triggers/a_table/a_trigger.sql"],
+                vec!["event_triggers/an_event_trigger.sql"],
+                vec![
+                    "security/functions/a_func/a_func().sql",
+                    "security/tables/a_table.sql",
+                ],
+            ]
+        );
+    }
+}
@@ -1,15 +1,27 @@
+pub mod ephemeral_db;
+pub mod property_test;
 pub mod test_config_manager;
 pub mod test_runner;
+pub mod test_scaffolder;
 
-use std::{collections::HashMap, path::Path};
+use std::{collections::BTreeMap, path::Path};
 
 use anyhow::{bail, Context, Result};
 use clap::Args;
 use colored::Colorize;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use regex::Regex;
 use sqlx::{Acquire, Postgres};
 
 use crate::{
-    actions::{init::SCHEMA_CONFIG_LOCATION, unit_test::test_runner::TestRunner},
+    actions::{
+        init::SCHEMA_CONFIG_LOCATION,
+        unit_test::{
+            ephemeral_db::EphemeralDb,
+            test_runner::{Shard, TestFilter, TestRunner},
+            test_scaffolder::TestScaffolder,
+        },
+    },
     config_file_manager::ddl_config::{
         get_commented_file_contents, get_matching_file_contents, get_uncommented_file_contents,
     },
@@ -20,12 +32,53 @@ use crate::{
 pub struct TestStats {
     pub num_passed: u32,
     pub num_failed: u32,
+    pub num_skipped: u32,
+}
+
+impl TestStats {
+    // Print whether the test defined in source passed or failed and add it to the tally
+    pub fn record(&mut self, source: &str, test_result: test_runner::TestResult) {
+        match test_result {
+            test_runner::TestResult::Passed { test_name } => {
+                println!(
+                    "\t{}::{} - {}",
+                    source.magenta(),
+                    test_name.bold(),
+                    "Passed".green()
+                );
+                self.num_passed += 1;
+            }
+            test_runner::TestResult::Failed {
+                test_name,
+                error_message,
+            } => {
+                println!(
+                    "\t{}::{} - {}",
+                    source.magenta(),
+                    test_name.bold(),
+                    "Failed".red()
+                );
+                println!("\t\t{}", error_message.replace('\n', "\n\t\t"));
+                self.num_failed += 1;
+            }
+            test_runner::TestResult::Skipped { test_name } => {
+                println!(
+                    "\t{}::{} - {}",
+                    source.magenta(),
+                    test_name.bold(),
+                    "Skipped".yellow()
+                );
+                self.num_skipped += 1;
+            }
+        }
+    }
 }
 
 impl std::ops::AddAssign for TestStats {
     fn add_assign(&mut self, rhs: Self) {
         self.num_passed += rhs.num_passed;
         self.num_failed += rhs.num_failed;
+        self.num_skipped += rhs.num_skipped;
     }
 }
 
@@ -36,19 +89,58 @@ pub struct UnitTest {
     /// Please note that this will run the functions in a transaction which will be rolled back at
     /// the completion of the unit tests. This does not guarentee no side effects if your function
     /// or procedure contains a COMMIT
-    #[clap(num_args = 0.., trailing_var_arg=true, index=1, required_unless_present_any(["all", "example"]))]
+    #[clap(num_args = 0.., index=1, required_unless_present_any(["all", "example", "scaffold"]))]
     // This is how you allow it to be a
     // positional argument rather than a flagged argument
     functions: Vec<String>,
 
     /// test all of the functions that specify unit tests from all schemas
-    #[arg(short, long, exclusive(true))]
+    #[arg(short, long, conflicts_with("functions"))]
     all: bool,
 
     /// Prints the contents of an example unit_test.yaml file. This is useful for getting started
     /// when creating new unit tests. Simply pipe the output of this command into a file.
     #[arg(long, exclusive(true))]
     example: bool,
+
+    /// Creates a skeleton unit test file for each overload of the given function, specified as
+    /// my_schema.func. The signature is read from the local function files if they have been
+    /// pulled, otherwise it is read from the database.
+    #[arg(long, exclusive(true), value_name = "SCHEMA.FUNCTION")]
+    scaffold: Option<String>,
+
+    /// Run the unit tests against a temporary database created on the configured server. Every
+    /// pulled file is applied to the temporary database before the tests are run and the database
+    /// is dropped afterwards, even if the tests fail or are interrupted.
+    #[arg(long)]
+    ephemeral: bool,
+
+    /// Only run the tests that have at least one of the given tags. Can be specified multiple times
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+
+    /// Skip the tests that have any of the given tags. Can be specified multiple times
+    #[arg(long = "skip-tag", value_name = "TAG")]
+    skip_tags: Vec<String>,
+
+    /// Only run the tests whose names match the given regular expression
+    #[arg(long, value_name = "REGEX")]
+    name: Option<String>,
+
+    /// Run the unit tests in a random order rather than sorted by schema, function and file. The
+    /// seed used is printed so that the order can be reproduced
+    #[arg(long)]
+    shuffle: bool,
+
+    /// The seed to shuffle the unit tests with. Use this to reproduce the order of a previous run
+    #[arg(long, requires = "shuffle")]
+    seed: Option<u64>,
+
+    /// Only run the given shard of the unit tests, specified as i/n (eg. 2/4 runs the second of
+    /// four shards). Each test always belongs to the same shard so that large test suites can be
+    /// split across CI jobs
+    #[arg(long, value_name = "i/n")]
+    shard: Option<Shard>,
 }
 
 impl UnitTest {
@@ -93,12 +185,14 @@ impl UnitTest {
     }
 
     // Get all locally defined functions and their unit test paths. First element of tuple is name
-    // of the function second is a map from the name to a vector of associated paths
-    fn get_func_unit_test_paths(schema: &str) -> Result<HashMap<String, Vec<String>>> {
-        let mut unit_test_paths: HashMap<String, Vec<String>> = HashMap::new();
-
-        let dir_walker =
-            walkdir::WalkDir::new(format!("./schemas/{}/functions", schema)).min_depth(3);
+    // of the function second is a map from the name to a vector of associated paths. Both the
+    // functions and the paths are sorted so that the tests are always run in the same order
+    fn get_func_unit_test_paths(schema: &str) -> Result<BTreeMap<String, Vec<String>>> {
+        let mut unit_test_paths: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        let dir_walker = walkdir::WalkDir::new(format!("./schemas/{}/functions", schema))
+            .min_depth(3)
+            .sort_by_file_name();
         for dir in dir_walker
             .into_iter()
             .filter_map(Result::ok)
@@ -126,42 +220,31 @@ impl UnitTest {
         Ok(unit_test_paths)
     }
 
-    async fn run_function_unit_test<'a, C>(conn: C, file_paths: &Vec<String>) -> Result<TestStats>
+    async fn run_function_unit_test<'a, C>(
+        conn: C,
+        file_paths: &[String],
+        filter: &TestFilter,
+        mut rng: Option<&mut StdRng>,
+    ) -> Result<TestStats>
     where
         C: Acquire<'a, Database = Postgres>,
     {
         let mut conn = conn.acquire().await?;
 
+        let mut file_paths = file_paths.to_vec();
+        if let Some(rng) = rng.as_deref_mut() {
+            file_paths.shuffle(rng);
+        }
+
         let mut test_stats = TestStats::default();
-        for fp in file_paths {
-            let test_runner = TestRunner::from_file(fp).await?;
-            let test_results = test_runner.run_tests(&mut *conn).await?;
+        for fp in &file_paths {
+            let mut test_runner = TestRunner::from_file(fp).await?;
+            if let Some(rng) = rng.as_deref_mut() {
+                test_runner.shuffle(rng);
+            }
+            let test_results = test_runner.run_tests(&mut *conn, filter).await?;
             for test_result in test_results {
-                // print the messages about pass or fail. add to the tally for passed vs failed
-                match test_result {
-                    test_runner::TestResult::Passed { test_name } => {
-                        println!(
-                            "\t{}::{} - {}",
-                            fp.magenta(),
-                            test_name.bold(),
-                            "Passed".green()
-                        );
-                        test_stats.num_passed += 1;
-                    }
-                    test_runner::TestResult::Failed {
-                        test_name,
-                        error_message,
-                    } => {
-                        println!(
-                            "\t{}::{} - {}",
-                            fp.magenta(),
-                            test_name.bold(),
-                            "Failed".red()
-                        );
-                        println!("\t\t{}", error_message.replace('\n', "\n\t\t"));
-                        test_stats.num_failed += 1;
-                    }
-                }
+                test_stats.record(fp, test_result);
             }
         }
         Ok(test_stats)
@@ -171,6 +254,8 @@ impl UnitTest {
         conn: C,
         functions: &[String],
         run_all: bool,
+        filter: &TestFilter,
+        shuffle_seed: Option<u64>,
     ) -> Result<TestStats>
     where
         C: Acquire<'c, Database = Postgres>,
@@ -178,10 +263,20 @@ impl UnitTest {
         let mut conn = conn.acquire().await?;
         let mut total_stats = TestStats::default();
 
-        let schemas = get_uncommented_file_contents(SCHEMA_CONFIG_LOCATION)?;
+        let mut schemas = get_uncommented_file_contents(SCHEMA_CONFIG_LOCATION)?;
+        schemas.sort();
 
         println!("\nBeginning Unit Tests:");
 
+        let mut rng = shuffle_seed.map(|seed| {
+            println!(
+                "Shuffling the unit tests with seed {}. Rerun with --shuffle --seed {} to reproduce this order",
+                seed.to_string().bold(),
+                seed
+            );
+            StdRng::seed_from_u64(seed)
+        });
+
         for schema in schemas {
             let unit_test_paths = Self::get_func_unit_test_paths(&schema)?;
             let commented_funcs = get_commented_file_contents(&format!(
@@ -193,39 +288,81 @@ impl UnitTest {
             let unit_test_paths = unit_test_paths
                 .into_iter()
                 .filter(|(item, _)| !commented_funcs.contains(item))
-                .collect::<HashMap<String, Vec<String>>>();
+                .collect::<BTreeMap<String, Vec<String>>>();
 
-            if run_all {
+            let mut funcs_to_test: Vec<&Vec<String>> = if run_all {
                 // If all is specified then just run all the local functions unit tests that aren't commented
-                if !unit_test_paths.is_empty() {
-                    println!("\nBeginning {} schema unit tests:", schema);
-                }
-                for (_, test_paths) in unit_test_paths.iter() {
-                    total_stats += Self::run_function_unit_test(&mut *conn, test_paths).await?;
-                }
+                unit_test_paths.values().collect()
             } else {
                 // Get the functions that match the patterns passed in
-                let matching_local_funcs =
-                    get_matching_file_contents(unit_test_paths.keys(), functions, Some(&schema))?;
-
-                if !matching_local_funcs.is_empty() {
-                    println!("\nBeginning {} schema unit tests:", schema);
-                }
-
-                for func in matching_local_funcs {
-                    total_stats += Self::run_function_unit_test(
-                        &mut *conn,
+                get_matching_file_contents(unit_test_paths.keys(), functions, Some(&schema))?
+                    .into_iter()
+                    .map(|func| {
                         unit_test_paths
                             .get(func)
-                            .context("The function path should match a function")?,
-                    )
-                    .await?;
-                }
+                            .context("The function path should match a function")
+                    })
+                    .collect::<Result<Vec<&Vec<String>>>>()?
+            };
+
+            if !funcs_to_test.is_empty() {
+                println!("\nBeginning {} schema unit tests:", schema);
+            }
+
+            if let Some(rng) = rng.as_mut() {
+                funcs_to_test.shuffle(rng);
+            }
+
+            for test_paths in funcs_to_test {
+                total_stats +=
+                    Self::run_function_unit_test(&mut *conn, test_paths, filter, rng.as_mut())
+                        .await?;
             }
         }
+
+        Self::print_summary(&total_stats);
         Ok(total_stats)
     }
 
+    fn print_summary(stats: &TestStats) {
+        let failed = match stats.num_failed {
+            0 => stats.num_failed.to_string().bold().green(),
+            _ => stats.num_failed.to_string().bold().red(),
+        };
+
+        println!(
+            "\nUnit Test Summary: Passed: {}, Failed: {}, Skipped: {}",
+            stats.num_passed.to_string().bold().green(),
+            failed,
+            stats.num_skipped.to_string().bold().yellow()
+        );
+    }
+
+    fn get_test_filter(&self) -> Result<TestFilter> {
+        let name = match &self.name {
+            Some(name) => Some(
+                Regex::new(name)
+                    .context(format!("The test name pattern '{}' is not a valid regex", name))?,
+            ),
+            None => None,
+        };
+
+        Ok(TestFilter {
+            tags: self.tags.clone(),
+            skip_tags: self.skip_tags.clone(),
+            name,
+            shard: self.shard.clone(),
+        })
+    }
+
+    fn get_shuffle_seed(&self) -> Option<u64> {
+        match (self.shuffle, self.seed) {
+            (true, Some(seed)) => Some(seed),
+            (true, None) => Some(rand::random()),
+            (false, _) => None,
+        }
+    }
+
     fn print_example() {
         const UNIT_TEST_EXAMPLE: &str = r#"
 - name: 'Example Multiple Rows and Columns in Output'
@@ -256,11 +393,71 @@ impl UnitTest {
             return Ok(());
         }
 
+        if let Some(scaffold) = &self.scaffold {
+            return TestScaffolder::scaffold(scaffold).await;
+        }
+
         let connection = db_manager::DbConnection::new().await?;
         let pool = connection.get_connection_pool();
 
-        Self::run_unit_tests(pool, &self.functions, self.all).await?;
+        if self.ephemeral {
+            return self.run_ephemeral_unit_tests(&connection).await;
+        }
+
+        Self::run_unit_tests(
+            pool,
+            &self.functions,
+            self.all,
+            &self.get_test_filter()?,
+            self.get_shuffle_seed(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn run_ephemeral_unit_tests(&self, connection: &db_manager::DbConnection) -> Result<()> {
+        let filter = self.get_test_filter()?;
+        let shuffle_seed = self.get_shuffle_seed();
+        let ephemeral_db = EphemeralDb::create(connection).await?;
+
+        let run_tests = async {
+            let schemas = get_uncommented_file_contents(SCHEMA_CONFIG_LOCATION)?;
+            ephemeral_db.apply_repository(&schemas).await?;
+
+            let ephemeral_pool = ephemeral_db.connect().await?;
+            let test_results = Self::run_unit_tests(
+                &ephemeral_pool,
+                &self.functions,
+                self.all,
+                &filter,
+                shuffle_seed,
+            )
+            .await;
+            ephemeral_pool.close().await;
+
+            test_results
+        };
+
+        // The database must always be dropped so don't return early on failure or interruption
+        let test_results = tokio::select! {
+            test_results = run_tests => test_results,
+            _ = tokio::signal::ctrl_c() => Err(anyhow::anyhow!("The unit tests were interrupted")),
+        };
+
+        // A failed drop is only a warning so that it doesn't hide the outcome of the tests
+        if let Err(err) = ephemeral_db
+            .drop_database(connection.get_connection_pool())
+            .await
+        {
+            println!(
+                "\n{}: Failed to drop the temporary database: {}",
+                "Warning".yellow(),
+                err
+            );
+        }
 
+        test_results?;
         Ok(())
     }
 }
@@ -0,0 +1,632 @@
+use anyhow::{bail, Result};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sqlx::{Acquire, Postgres, Row};
+
+use crate::{
+    actions::unit_test::{test_config_manager::PropertyTestConfig, test_runner::TestResult},
+    db_manager::error_handling::get_db_error,
+};
+
+const DEFAULT_RUNS: u32 = 100;
+const MAX_SHRINK_ATTEMPTS: u32 = 1000;
+const MAX_TEXT_LENGTH: usize = 20;
+const MAX_ARRAY_LENGTH: usize = 5;
+const TEXT_CHARS: &[char] = &[
+    'a', 'b', 'c', 'x', 'y', 'z', 'A', 'Z', '0', '1', '9', ' ', '_', '-', '.', ',', '\'', '"',
+    '\\', '%', '\n', 'é', 'ß', '中', '😀',
+];
+
+// Generates random values for a single Postgres type
+#[derive(Debug, Clone, PartialEq)]
+pub enum Generator {
+    Int { min: i64, max: i64 },
+    Float,
+    Numeric { max: i64, scale: u32 }, // max is the largest unscaled value
+    Text,
+    Bool,
+    Date,
+    Timestamp,
+    Enum(Vec<String>),
+    Array(Box<Generator>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    Null,
+    Int(i64),
+    Float(f64),
+    Numeric(i64), // The unscaled value, ie. 1.23 with a scale of 2 is 123
+    Text(String),
+    Bool(bool),
+    Days(i64),    // Days since 2000-01-01
+    Seconds(i64), // Seconds since 2000-01-01 00:00:00
+    Enum(usize),  // The index of the label
+    Array(Vec<PropertyValue>),
+}
+
+impl Generator {
+    // Get the generator for the type. Types that aren't built in are assumed to be enums whose
+    // labels are read from the database
+    pub async fn for_type<'a, C>(conn: C, pg_type: &str) -> Result<Self>
+    where
+        C: Acquire<'a, Database = Postgres>,
+    {
+        let mut conn = conn.acquire().await?;
+        let normalised_type = pg_type.trim().to_lowercase();
+
+        if let Some(element_type) = normalised_type.strip_suffix("[]") {
+            let element_generator = Box::pin(Self::for_type(&mut *conn, element_type)).await?;
+            return Ok(Self::Array(Box::new(element_generator)));
+        }
+
+        if let Some(generator) = Self::for_builtin_type(&normalised_type) {
+            return Ok(generator);
+        }
+
+        let labels: Vec<String> = sqlx::query(
+            "SELECT enumlabel::TEXT FROM pg_enum WHERE enumtypid = $1::TEXT::regtype ORDER BY enumsortorder",
+        )
+        .bind(pg_type.trim())
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| anyhow::anyhow!(get_db_error(e)))?
+        .into_iter()
+        .map(|row| row.try_get(0))
+        .collect::<Result<Vec<String>, sqlx::Error>>()?;
+
+        if labels.is_empty() {
+            bail!(
+                "Random values can not be generated for the type '{}'. Only integers, numerics, floats, text, booleans, dates, timestamps, enums and arrays of these are supported",
+                pg_type
+            );
+        }
+
+        Ok(Self::Enum(labels))
+    }
+
+    pub fn for_builtin_type(normalised_type: &str) -> Option<Self> {
+        // Strip any type modifiers such as varchar(10) or numeric(10,2)
+        let (base_type, modifiers) = match normalised_type.split_once('(') {
+            Some((base_type, modifiers)) => {
+                (base_type.trim(), Some(modifiers.trim_end_matches(')')))
+            }
+            None => (normalised_type, None),
+        };
+
+        let generator = match base_type {
+            "smallint" | "int2" => Self::Int {
+                min: i16::MIN as i64,
+                max: i16::MAX as i64,
+            },
+            "integer" | "int" | "int4" => Self::Int {
+                min: i32::MIN as i64,
+                max: i32::MAX as i64,
+            },
+            "bigint" | "int8" => Self::Int {
+                min: i64::MIN,
+                max: i64::MAX,
+            },
+            "real" | "float4" | "double precision" | "float8" | "float" => Self::Float,
+            "numeric" | "decimal" => {
+                let modifiers: Vec<u32> = modifiers
+                    .map(|modifiers| {
+                        modifiers
+                            .split(',')
+                            .filter_map(|modifier| modifier.trim().parse().ok())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                // The scale is 0 when only the precision is given. Values are limited to the
+                // digits of the precision so that they never overflow the type
+                let max_for_precision = |precision: u32| 10_i64.pow(precision.min(18)) - 1;
+                match modifiers[..] {
+                    [precision, scale] => Self::Numeric {
+                        max: max_for_precision(precision),
+                        scale,
+                    },
+                    [precision] => Self::Numeric {
+                        max: max_for_precision(precision),
+                        scale: 0,
+                    },
+                    _ => Self::Numeric {
+                        max: 100_000_000,
+                        scale: 2,
+                    },
+                }
+            }
+            "text" | "varchar" | "character varying" | "char" | "character" | "bpchar" | "name"
+            | "citext" => Self::Text,
+            "boolean" | "bool" => Self::Bool,
+            "date" => Self::Date,
+            "timestamp"
+            | "timestamp without time zone"
+            | "timestamptz"
+            | "timestamp with time zone" => Self::Timestamp,
+            _ => return None,
+        };
+
+        Some(generator)
+    }
+
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> PropertyValue {
+        match self {
+            Self::Int { min, max } => {
+                // Bias towards edge cases as they are the most likely to find bugs
+                if rng.gen_ratio(1, 5) {
+                    let edge_cases = [0, 1, -1, *min, *max];
+                    return PropertyValue::Int(
+                        edge_cases[rng.gen_range(0..edge_cases.len())].clamp(*min, *max),
+                    );
+                }
+                PropertyValue::Int(rng.gen_range(*min..=*max))
+            }
+            Self::Float => PropertyValue::Float(rng.gen_range(-1_000_000.0..1_000_000.0)),
+            Self::Numeric { max, .. } => PropertyValue::Numeric(rng.gen_range(-*max..=*max)),
+            Self::Text => {
+                let length = rng.gen_range(0..=MAX_TEXT_LENGTH);
+                PropertyValue::Text(
+                    (0..length)
+                        .map(|_| TEXT_CHARS[rng.gen_range(0..TEXT_CHARS.len())])
+                        .collect(),
+                )
+            }
+            Self::Bool => PropertyValue::Bool(rng.gen()),
+            Self::Date => PropertyValue::Days(rng.gen_range(-36_500..=36_500)),
+            Self::Timestamp => {
+                PropertyValue::Seconds(rng.gen_range(-3_153_600_000..=3_153_600_000))
+            }
+            Self::Enum(labels) => PropertyValue::Enum(rng.gen_range(0..labels.len())),
+            Self::Array(element_generator) => {
+                let length = rng.gen_range(0..=MAX_ARRAY_LENGTH);
+                PropertyValue::Array(
+                    (0..length)
+                        .map(|_| element_generator.generate(rng))
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    // Render the value as a SQL literal of the given type
+    pub fn to_sql(&self, value: &PropertyValue, pg_type: &str) -> String {
+        let literal = match (self, value) {
+            (_, PropertyValue::Null) => return format!("NULL::{}", pg_type),
+            (_, PropertyValue::Int(n)) => n.to_string(),
+            (_, PropertyValue::Float(f)) => format!("'{}'", f),
+            (Self::Numeric { scale, .. }, PropertyValue::Numeric(n)) => {
+                format!("'{}e-{}'", n, scale)
+            }
+            (_, PropertyValue::Text(text)) => format!("'{}'", text.replace('\'', "''")),
+            (_, PropertyValue::Bool(b)) => b.to_string(),
+            (_, PropertyValue::Days(days)) => format!("'2000-01-01'::DATE + {}", days),
+            (_, PropertyValue::Seconds(seconds)) => format!(
+                "'2000-01-01 00:00:00'::TIMESTAMP + {} * INTERVAL '1 second'",
+                seconds
+            ),
+            (Self::Enum(labels), PropertyValue::Enum(idx)) => {
+                format!("'{}'", labels[*idx].replace('\'', "''"))
+            }
+            (Self::Array(element_generator), PropertyValue::Array(elements)) => {
+                if elements.is_empty() {
+                    return format!("'{{}}'::{}", pg_type);
+                }
+                let element_type = pg_type.trim().trim_end_matches("[]");
+                format!(
+                    "ARRAY[{}]",
+                    elements
+                        .iter()
+                        .map(|element| element_generator.to_sql(element, element_type))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            }
+            _ => unreachable!("Values are always rendered by the generator that created them"),
+        };
+
+        format!("({})::{}", literal, pg_type)
+    }
+
+    // Get simpler versions of the value to try when shrinking a failing input
+    pub fn shrink(&self, value: &PropertyValue) -> Vec<PropertyValue> {
+        let shrink_int = |n: i64| -> Vec<i64> {
+            let mut candidates = vec![0, n / 2, n - n.signum()];
+            candidates.dedup();
+            candidates.into_iter().filter(|c| *c != n).collect()
+        };
+
+        match (self, value) {
+            (_, PropertyValue::Int(n)) => {
+                shrink_int(*n).into_iter().map(PropertyValue::Int).collect()
+            }
+            (_, PropertyValue::Numeric(n)) => shrink_int(*n)
+                .into_iter()
+                .map(PropertyValue::Numeric)
+                .collect(),
+            (_, PropertyValue::Days(n)) => shrink_int(*n)
+                .into_iter()
+                .map(PropertyValue::Days)
+                .collect(),
+            (_, PropertyValue::Seconds(n)) => shrink_int(*n)
+                .into_iter()
+                .map(PropertyValue::Seconds)
+                .collect(),
+            (_, PropertyValue::Float(f)) => [0.0, (f / 2.0).trunc(), f.trunc()]
+                .into_iter()
+                .filter(|c| c != f)
+                .map(PropertyValue::Float)
+                .collect(),
+            (_, PropertyValue::Text(text)) => {
+                let chars: Vec<char> = text.chars().collect();
+                let mut candidates: Vec<String> = vec![
+                    String::new(),
+                    chars[..chars.len() / 2].iter().collect(),
+                    chars[..chars.len().saturating_sub(1)].iter().collect(),
+                    chars.iter().skip(1).collect(),
+                ];
+                candidates.dedup();
+                candidates
+                    .into_iter()
+                    .filter(|c| c != text)
+                    .map(PropertyValue::Text)
+                    .collect()
+            }
+            (_, PropertyValue::Bool(true)) => vec![PropertyValue::Bool(false)],
+            (_, PropertyValue::Enum(idx)) if *idx > 0 => vec![PropertyValue::Enum(0)],
+            (Self::Array(element_generator), PropertyValue::Array(elements)) => {
+                if elements.is_empty() {
+                    return vec![];
+                }
+
+                let mut candidates = vec![
+                    PropertyValue::Array(vec![]),
+                    PropertyValue::Array(elements[..elements.len() / 2].to_vec()),
+                ];
+                for idx in 0..elements.len() {
+                    let mut without_element = elements.clone();
+                    without_element.remove(idx);
+                    candidates.push(PropertyValue::Array(without_element));
+
+                    if let Some(shrunk_element) =
+                        element_generator.shrink(&elements[idx]).into_iter().next()
+                    {
+                        let mut with_shrunk_element = elements.clone();
+                        with_shrunk_element[idx] = shrunk_element;
+                        candidates.push(PropertyValue::Array(with_shrunk_element));
+                    }
+                }
+                candidates.dedup();
+                candidates.retain(|c| c != value);
+                candidates
+            }
+            _ => vec![],
+        }
+    }
+}
+
+// Replace the $1, $2... placeholders within the check with the generated argument literals
+pub fn bind_args(check: &str, args: &[String]) -> String {
+    let mut bound = String::with_capacity(check.len());
+    let mut chars = check.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        if c != '$' {
+            bound.push(c);
+            continue;
+        }
+
+        let mut end = idx + 1;
+        while let Some((digit_idx, digit)) = chars.peek() {
+            if !digit.is_ascii_digit() {
+                break;
+            }
+            end = digit_idx + 1;
+            chars.next();
+        }
+
+        match check[idx + 1..end].parse::<usize>() {
+            Ok(arg_num) if arg_num >= 1 && arg_num <= args.len() => {
+                bound.push_str(&args[arg_num - 1])
+            }
+            _ => bound.push_str(&check[idx..end]),
+        }
+    }
+
+    bound
+}
+
+// Runs a property test by checking that the property holds for randomly generated arguments
+pub struct PropertyTestRunner<'t> {
+    test_name: &'t str,
+    config: &'t PropertyTestConfig,
+    generators: Vec<Generator>,
+}
+
+impl<'t> PropertyTestRunner<'t> {
+    pub async fn new<'a, C>(
+        conn: C,
+        test_name: &'t str,
+        config: &'t PropertyTestConfig,
+    ) -> Result<Self>
+    where
+        C: Acquire<'a, Database = Postgres>,
+    {
+        let mut conn = conn.acquire().await?;
+
+        let mut generators = Vec::with_capacity(config.arg_types.len());
+        for arg_type in &config.arg_types {
+            generators.push(Generator::for_type(&mut *conn, arg_type).await?);
+        }
+
+        Ok(Self {
+            test_name,
+            config,
+            generators,
+        })
+    }
+
+    fn render_args(&self, args: &[PropertyValue]) -> Vec<String> {
+        self.generators
+            .iter()
+            .zip(args)
+            .zip(&self.config.arg_types)
+            .map(|((generator, value), pg_type)| generator.to_sql(value, pg_type))
+            .collect()
+    }
+
+    // Returns the reason the check failed for the given arguments, or None if the property holds
+    async fn check<'a, C>(&self, conn: C, args: &[PropertyValue]) -> Result<Option<String>>
+    where
+        C: Acquire<'a, Database = Postgres>,
+    {
+        let mut conn = conn.acquire().await?;
+        let query = bind_args(&self.config.check, &self.render_args(args));
+
+        // Run each check within a savepoint so that an error does not abort the whole test
+        let mut transaction = conn.begin().await?;
+        let result = match sqlx::query(&query).fetch_all(&mut *transaction).await {
+            Ok(rows) => match rows.as_slice() {
+                [row] => match row.try_get::<Option<bool>, _>(0) {
+                    Ok(Some(true)) => None,
+                    Ok(Some(false)) => Some(String::from("The check returned false")),
+                    Ok(None) => Some(String::from("The check returned NULL")),
+                    Err(e) => Some(format!("The check must return a boolean: {}", e)),
+                },
+                _ => Some(format!(
+                    "The check must return exactly one row but returned {}",
+                    rows.len()
+                )),
+            },
+            Err(e) => Some(get_db_error(e)),
+        };
+        transaction.rollback().await?;
+
+        Ok(result)
+    }
+
+    pub async fn run<'a, C>(&self, conn: C) -> Result<TestResult>
+    where
+        C: Acquire<'a, Database = Postgres>,
+    {
+        let mut conn = conn.acquire().await?;
+
+        let seed = self.config.seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let runs = self.config.runs.unwrap_or(DEFAULT_RUNS);
+        let include_nulls = self.config.include_nulls.unwrap_or(false);
+
+        for run in 1..=runs {
+            let args: Vec<PropertyValue> = self
+                .generators
+                .iter()
+                .map(|generator| {
+                    if include_nulls && rng.gen_ratio(1, 10) {
+                        return PropertyValue::Null;
+                    }
+                    generator.generate(&mut rng)
+                })
+                .collect();
+
+            if let Some(reason) = self.check(&mut *conn, &args).await? {
+                let (args, reason) = self.shrink(&mut *conn, args, reason).await?;
+
+                return Ok(TestResult::Failed {
+                    test_name: self.test_name.to_string(),
+                    error_message: format!(
+                        "Property: Failed on run {} of {} (seed: {}). Add 'seed: {}' to the property to reproduce\nShrunk arguments: {}\n{}",
+                        run,
+                        runs,
+                        seed,
+                        seed,
+                        self.render_args(&args)
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, arg)| format!("${} = {}", idx + 1, arg))
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                        reason
+                    ),
+                });
+            }
+        }
+
+        Ok(TestResult::Passed {
+            test_name: self.test_name.to_string(),
+        })
+    }
+
+    // Repeatedly simplify the failing arguments for as long as the check keeps failing
+    async fn shrink<'a, C>(
+        &self,
+        conn: C,
+        mut args: Vec<PropertyValue>,
+        mut reason: String,
+    ) -> Result<(Vec<PropertyValue>, String)>
+    where
+        C: Acquire<'a, Database = Postgres>,
+    {
+        let mut conn = conn.acquire().await?;
+        let mut attempts = 0;
+
+        'shrinking: loop {
+            for (idx, generator) in self.generators.iter().enumerate() {
+                for candidate in generator.shrink(&args[idx]) {
+                    attempts += 1;
+                    if attempts > MAX_SHRINK_ATTEMPTS {
+                        break 'shrinking;
+                    }
+
+                    let mut candidate_args = args.clone();
+                    candidate_args[idx] = candidate;
+
+                    if let Some(candidate_reason) = self.check(&mut *conn, &candidate_args).await? {
+                        args = candidate_args;
+                        reason = candidate_reason;
+                        continue 'shrinking;
+                    }
+                }
+            }
+
+            // None of the simpler arguments failed so this is as small as it gets
+            break;
+        }
+
+        Ok((args, reason))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_values_are_reproducible() {
+        let generators = [
+            Generator::for_builtin_type("integer").unwrap(),
+            Generator::for_builtin_type("text").unwrap(),
+            Generator::Array(Box::new(
+                Generator::for_builtin_type("numeric(10,3)").unwrap(),
+            )),
+        ];
+
+        let generate = |seed: u64| -> Vec<PropertyValue> {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20)
+                .flat_map(|_| {
+                    generators
+                        .iter()
+                        .map(|g| g.generate(&mut rng))
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        };
+
+        assert_eq!(generate(42), generate(42));
+        assert_ne!(generate(42), generate(43));
+    }
+
+    #[test]
+    fn to_sql_works() {
+        let int_generator = Generator::for_builtin_type("int4").unwrap();
+        assert_eq!(
+            int_generator.to_sql(&PropertyValue::Int(-5), "int4"),
+            "(-5)::int4"
+        );
+        assert_eq!(
+            int_generator.to_sql(&PropertyValue::Null, "int4"),
+            "NULL::int4"
+        );
+
+        let text_generator = Generator::for_builtin_type("text").unwrap();
+        assert_eq!(
+            text_generator.to_sql(&PropertyValue::Text(String::from("it's")), "text"),
+            "('it''s')::text"
+        );
+
+        let numeric_generator = Generator::for_builtin_type("numeric(10, 3)").unwrap();
+        assert_eq!(
+            numeric_generator.to_sql(&PropertyValue::Numeric(1234), "numeric(10, 3)"),
+            "('1234e-3')::numeric(10, 3)"
+        );
+
+        let array_generator = Generator::Array(Box::new(Generator::Enum(vec![
+            String::from("red"),
+            String::from("blue"),
+        ])));
+        assert_eq!(
+            array_generator.to_sql(
+                &PropertyValue::Array(vec![PropertyValue::Enum(1), PropertyValue::Enum(0)]),
+                "colour[]"
+            ),
+            "(ARRAY[('blue')::colour, ('red')::colour])::colour[]"
+        );
+        assert_eq!(
+            array_generator.to_sql(&PropertyValue::Array(vec![]), "colour[]"),
+            "'{}'::colour[]"
+        );
+    }
+
+    #[test]
+    fn numeric_values_fit_the_precision() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let numeric_generator = Generator::for_builtin_type("numeric(5,2)").unwrap();
+        assert_eq!(numeric_generator, Generator::Numeric { max: 99_999, scale: 2 });
+        for _ in 0..100 {
+            match numeric_generator.generate(&mut rng) {
+                PropertyValue::Numeric(n) => assert!((-99_999..=99_999).contains(&n)),
+                value => panic!("Expected a numeric value, got {:?}", value),
+            }
+        }
+        assert_eq!(
+            numeric_generator.to_sql(&PropertyValue::Numeric(99_999), "numeric(5,2)"),
+            "('99999e-2')::numeric(5,2)"
+        );
+
+        let numeric_generator = Generator::for_builtin_type("numeric(5)").unwrap();
+        assert_eq!(numeric_generator, Generator::Numeric { max: 99_999, scale: 0 });
+        assert_eq!(
+            numeric_generator.to_sql(&PropertyValue::Numeric(-99_999), "numeric(5)"),
+            "('-99999e-0')::numeric(5)"
+        );
+    }
+
+    #[test]
+    fn shrink_works() {
+        let int_generator = Generator::for_builtin_type("integer").unwrap();
+        assert_eq!(
+            int_generator.shrink(&PropertyValue::Int(10)),
+            vec![
+                PropertyValue::Int(0),
+                PropertyValue::Int(5),
+                PropertyValue::Int(9)
+            ]
+        );
+        assert!(int_generator.shrink(&PropertyValue::Int(0)).is_empty());
+
+        let text_generator = Generator::for_builtin_type("text").unwrap();
+        assert_eq!(
+            text_generator.shrink(&PropertyValue::Text(String::from("ab"))),
+            vec![
+                PropertyValue::Text(String::new()),
+                PropertyValue::Text(String::from("a")),
+                PropertyValue::Text(String::from("b")),
+            ]
+        );
+        assert!(text_generator
+            .shrink(&PropertyValue::Text(String::new()))
+            .is_empty());
+    }
+
+    #[test]
+    fn bind_args_works() {
+        let args = vec![String::from("(1)::integer"), String::from("('a')::text")];
+        assert_eq!(
+            bind_args(
+                "SELECT $1 + 1 > $1 AND length($2) = 1 AND $10 IS NULL",
+                &args
+            ),
+            "SELECT (1)::integer + 1 > (1)::integer AND length(('a')::text) = 1 AND $10 IS NULL"
+        );
+    }
+}
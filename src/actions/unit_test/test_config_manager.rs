@@ -9,14 +9,27 @@ pub struct TestSideEffectConfig {
     pub table_query: String,
     pub expected_query_results: Vec<HashMap<String, String>>,
 }
+
+// A check that must hold for randomly generated arguments of the given types
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PropertyTestConfig {
+    pub arg_types: Vec<String>,     // The Postgres types of the generated arguments $1, $2 etc
+    pub check: String,              // A query returning a single boolean using $1, $2 etc
+    pub runs: Option<u32>,          // The number of argument tuples to try, defaults to 100
+    pub seed: Option<u64>,          // Set to reproduce a previous run
+    pub include_nulls: Option<bool>, // Whether NULL is generated as an argument
+}
 //
 // The definition of a test from the json files
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TestConfig {
     pub name: String,
+    #[serde(default)]
     pub query: String,
     pub expected_output: Option<Vec<HashMap<String, String>>>,
     pub expected_side_effect: Option<TestSideEffectConfig>,
+    pub tags: Option<Vec<String>>,
+    pub property: Option<PropertyTestConfig>,
 }
 
 // Manage the config files such as getting tests etc
@@ -57,6 +70,7 @@ mod tests {
       col2: '2'
     - col1: '3'
       col2: '4'
+  tags: [slow, integration]
             "#;
 
             let config: Vec<TestConfig> = serde_yaml::from_str(example_json).unwrap();
@@ -95,6 +109,37 @@ mod tests {
                         ]
                     })
             );
+
+            assert!(
+                config[0].tags == Some(vec!["slow".to_string(), "integration".to_string()])
+            );
+            assert!(config[0].property.is_none());
+        }
+
+        #[test]
+        fn deserialize_property_works() {
+            let example_yaml = r#"
+- name: Concatenating adds the lengths
+  property:
+    arg_types: [text, text]
+    check: SELECT length(public.concatenating($1, $2)) = length($1) + length($2);
+    runs: 50
+    seed: 42
+            "#;
+
+            let config: Vec<TestConfig> = serde_yaml::from_str(example_yaml).unwrap();
+
+            assert!(config[0].query.is_empty());
+            assert!(
+                config[0].property
+                    == Some(PropertyTestConfig {
+                        arg_types: vec!["text".to_string(), "text".to_string()],
+                        check: "SELECT length(public.concatenating($1, $2)) = length($1) + length($2);".to_string(),
+                        runs: Some(50),
+                        seed: Some(42),
+                        include_nulls: None,
+                    })
+            );
         }
     }
 }
@@ -1,11 +1,16 @@
 use std::collections::HashMap;
 
 use crate::{
-    actions::unit_test::test_config_manager::{get_test_config, TestConfig},
+    actions::unit_test::{
+        property_test::PropertyTestRunner,
+        test_config_manager::{get_test_config, TestConfig},
+    },
     db_manager::error_handling::get_db_error,
 };
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use futures::TryStreamExt;
+use rand::{seq::SliceRandom, Rng};
+use regex::Regex;
 use sqlx::{postgres::PgRow, Column, Executor, Row, ValueRef, Postgres, Acquire};
 
 use super::test_config_manager::TestSideEffectConfig;
@@ -19,23 +24,113 @@ pub enum TestResult {
         test_name: String,
         error_message: String,
     },
+    Skipped {
+        test_name: String,
+    },
+}
+
+// One of count shards of the unit tests. The index starts at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shard {
+    pub index: u64,
+    pub count: u64,
+}
+
+impl std::str::FromStr for Shard {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (index, count) = s
+            .split_once('/')
+            .context("The shard must be specified as i/n, for example 1/4")?;
+        let index: u64 = index.trim().parse().context("The shard index must be a number")?;
+        let count: u64 = count.trim().parse().context("The shard count must be a number")?;
+
+        if count == 0 || index == 0 || index > count {
+            bail!("The shard index must be between 1 and the number of shards");
+        }
+
+        Ok(Self { index, count })
+    }
+}
+
+impl Shard {
+    // Tests are assigned to shards by a hash of their file path and name so that a test always
+    // ends up in the same shard no matter which other tests exist. FNV-1a is used because unlike
+    // the std hasher its output is guaranteed to be stable
+    pub fn contains(&self, test_id: &str) -> bool {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in test_id.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        hash % self.count == self.index - 1
+    }
+}
+
+// Decides which individual tests are run based on their tags and names
+#[derive(Debug, Default)]
+pub struct TestFilter {
+    pub tags: Vec<String>,      // Only run tests with at least one of these tags
+    pub skip_tags: Vec<String>, // Skip tests with any of these tags
+    pub name: Option<Regex>,    // Only run tests whose names match this pattern
+    pub shard: Option<Shard>,   // Only run tests that belong to this shard
+}
+
+impl TestFilter {
+    // Tests outside of the shard belong to a different CI job so they are not run or reported
+    pub fn in_shard(&self, test_id: &str) -> bool {
+        match &self.shard {
+            Some(shard) => shard.contains(test_id),
+            None => true,
+        }
+    }
+
+    pub fn should_run(&self, test: &TestConfig) -> bool {
+        let test_tags = test.tags.as_deref().unwrap_or_default();
+
+        if !self.tags.is_empty() && !self.tags.iter().any(|tag| test_tags.contains(tag)) {
+            return false;
+        }
+
+        if self.skip_tags.iter().any(|tag| test_tags.contains(tag)) {
+            return false;
+        }
+
+        match &self.name {
+            Some(name) => name.is_match(&test.name),
+            None => true,
+        }
+    }
 }
 
 // runs a test given a unit test definition
 pub struct TestRunner {
     tests: Vec<TestConfig>,
+    source: String, // The file the tests were defined in
 }
 
 impl TestRunner {
     pub fn new(tests: Vec<TestConfig>) -> Self {
-        Self { tests }
+        Self {
+            tests,
+            source: String::new(),
+        }
     }
 
     pub async fn from_file(file_path: &str) -> Result<Self> {
-        Ok(Self::new(get_test_config(file_path).await?))
+        Ok(Self {
+            tests: get_test_config(file_path).await?,
+            source: file_path.to_string(),
+        })
     }
 
-    pub async fn run_tests<'a, C>(&self, conn: C) -> Result<Vec<TestResult>> 
+    pub fn shuffle<R: Rng>(&mut self, rng: &mut R) {
+        self.tests.shuffle(rng);
+    }
+
+    pub async fn run_tests<'a, C>(&self, conn: C, filter: &TestFilter) -> Result<Vec<TestResult>> 
     where C: Acquire<'a, Database = Postgres>
     {
         let mut conn = conn.acquire().await?;
@@ -47,6 +142,17 @@ impl TestRunner {
         }
         let mut test_results = Vec::with_capacity(self.tests.len());
         for test in &self.tests {
+            if !filter.in_shard(&format!("{}::{}", self.source, test.name)) {
+                continue;
+            }
+
+            if !filter.should_run(test) {
+                test_results.push(TestResult::Skipped {
+                    test_name: test.name.clone(),
+                });
+                continue;
+            }
+
             let mut transaction = conn.begin().await?;
             test_results.push(self.run_test(&mut *transaction, test).await?);
             transaction.rollback().await?;
@@ -61,6 +167,22 @@ impl TestRunner {
         let query: &str = &test.query;
         let mut conn = conn.acquire().await?;
 
+        if let Some(property) = &test.property {
+            // An argument type that values can't be generated for only fails this test so that
+            // the rest of the tests are still run
+            let property_test_runner =
+                match PropertyTestRunner::new(&mut *conn, &test.name, property).await {
+                    Ok(property_test_runner) => property_test_runner,
+                    Err(e) => {
+                        return Ok(TestResult::Failed {
+                            test_name: test.name.clone(),
+                            error_message: format!("Property: {}", e),
+                        })
+                    }
+                };
+            return property_test_runner.run(&mut *conn).await;
+        }
+
         let func_output_result = match &test.expected_output {
             Some(expected) => {
                 self.check_query_results(&mut *conn, query, expected, &test.name, "Query Result")
@@ -111,7 +233,7 @@ impl TestRunner {
         &self,
         conn: C,
         query: &str,
-        expected_result: &Vec<HashMap<String, String>>,
+        expected_result: &[HashMap<String, String>],
         test_name: &str,
         test_prefix: &str,
     ) -> Result<TestResult> 
@@ -230,7 +352,7 @@ mod tests {
         let test_runner = TestRunner::new(test_config);
 
         let pool = db_connection.get_connection_pool();
-        let results = tokio_test::block_on(test_runner.run_tests(pool)).expect("This to not fail");
+        let results = tokio_test::block_on(test_runner.run_tests(pool, &TestFilter::default())).expect("This to not fail");
 
         assert_eq!(
             results[0],
@@ -326,7 +448,7 @@ mod tests {
 
         let test_runner = TestRunner::new(test_config);
 
-        let results = tokio_test::block_on(test_runner.run_tests(pool)).expect("This to not fail");
+        let results = tokio_test::block_on(test_runner.run_tests(pool, &TestFilter::default())).expect("This to not fail");
 
         assert_eq!(
             results[0],
@@ -354,4 +476,116 @@ mod tests {
 
         tokio_test::block_on(pool.execute("DROP TABLE IF EXISTS public.tusk_test;")).unwrap();
     }
+
+    #[test]
+    fn unsupported_property_types_only_fail_their_test() {
+        let db_connection = tokio_test::block_on(DbConnection::new()).unwrap();
+
+        let test_config_text = r#"
+- name: 'Unsupported Property'
+  property:
+    arg_types: [tusk_not_a_type]
+    check: SELECT $1 IS NOT NULL;
+- name: 'Passing Test'
+  query: 'SELECT 1 AS one;'
+  expected_output:
+  - one: '1'
+        "#;
+
+        let test_config: Vec<TestConfig> =
+            serde_yaml::from_str(test_config_text).expect("This should never fail");
+
+        let test_runner = TestRunner::new(test_config);
+
+        let pool = db_connection.get_connection_pool();
+        let results = tokio_test::block_on(test_runner.run_tests(pool, &TestFilter::default())).expect("This to not fail");
+
+        assert!(matches!(
+            &results[0],
+            TestResult::Failed { test_name, error_message }
+                if test_name == "Unsupported Property" && error_message.starts_with("Property: ")
+        ));
+        assert_eq!(
+            results[1],
+            TestResult::Passed {
+                test_name: "Passing Test".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_filter_works() {
+        let test_config_text = r#"
+- name: 'Untagged Test'
+  query: 'SELECT 1;'
+  expected_output:
+- name: 'Slow Test'
+  query: 'SELECT 1;'
+  expected_output:
+  tags: [slow]
+- name: 'Slow Integration Test'
+  query: 'SELECT 1;'
+  expected_output:
+  tags: [slow, integration]
+        "#;
+
+        let test_config: Vec<TestConfig> =
+            serde_yaml::from_str(test_config_text).expect("This should never fail");
+
+        let run_names = |filter: &TestFilter| -> Vec<&str> {
+            test_config
+                .iter()
+                .filter(|test| filter.should_run(test))
+                .map(|test| test.name.as_str())
+                .collect()
+        };
+
+        assert_eq!(
+            run_names(&TestFilter::default()),
+            vec!["Untagged Test", "Slow Test", "Slow Integration Test"]
+        );
+
+        assert_eq!(
+            run_names(&TestFilter {
+                tags: vec!["slow".to_string()],
+                skip_tags: vec!["integration".to_string()],
+                name: None,
+                shard: None,
+            }),
+            vec!["Slow Test"]
+        );
+
+        assert_eq!(
+            run_names(&TestFilter {
+                tags: vec![],
+                skip_tags: vec![],
+                name: Some(Regex::new("^(Untagged|Slow Int)").unwrap()),
+                shard: None,
+            }),
+            vec!["Untagged Test", "Slow Integration Test"]
+        );
+    }
+
+    #[test]
+    fn shards_work() {
+        assert!("0/4".parse::<Shard>().is_err());
+        assert!("5/4".parse::<Shard>().is_err());
+        assert!("1-4".parse::<Shard>().is_err());
+
+        let shards: Vec<Shard> = (1..=3)
+            .map(|idx| format!("{}/3", idx).parse().unwrap())
+            .collect();
+
+        // Every test should belong to exactly one shard
+        for test_num in 0..100 {
+            let test_id = format!("./schemas/public/functions/f/unit_tests/t.yaml::Test {}", test_num);
+            assert_eq!(
+                shards.iter().filter(|shard| shard.contains(&test_id)).count(),
+                1
+            );
+        }
+
+        // The shard a test belongs to must never change between runs
+        assert!(Shard { index: 3, count: 3 }.contains("stable test id"));
+    }
 }
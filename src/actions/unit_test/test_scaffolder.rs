@@ -0,0 +1,238 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+use crate::{
+    actions::function_signature::{quote_ident, ArgMode, FunctionSignature},
+    db_manager,
+};
+
+// Creates skeleton unit test files from the signatures of a functions overloads
+pub struct TestScaffolder {}
+
+impl TestScaffolder {
+    // Get the signatures of the overloads from the local function files if they have been pulled,
+    // otherwise fall back to the database catalog
+    async fn get_signatures(schema: &str, function_name: &str) -> Result<Vec<FunctionSignature>> {
+        let function_dir = format!(
+            "./schemas/{}/functions/{}",
+            schema,
+            quote_ident(function_name)
+        );
+
+        let mut signatures = vec![];
+        for entry in walkdir::WalkDir::new(&function_dir)
+            .min_depth(1)
+            .max_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+        {
+            let file_path = entry.path();
+            if file_path.extension().and_then(|ext| ext.to_str()) != Some("sql") {
+                continue;
+            }
+
+            let overload_name = file_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .context("The file paths provided should be valid UTF-8 Characters")?;
+            let definition = tokio::fs::read_to_string(file_path).await?;
+
+            signatures.push(
+                FunctionSignature::from_definition(schema, overload_name, &definition).context(
+                    format!("Could not parse the function signature in {:?}", file_path),
+                )?,
+            );
+        }
+
+        if !signatures.is_empty() {
+            return Ok(signatures);
+        }
+
+        println!(
+            "\tNo local definition found at {}, reading the signature from the database",
+            function_dir.magenta()
+        );
+        let connection = db_manager::DbConnection::new().await?;
+        FunctionSignature::from_catalog(connection.get_connection_pool(), schema, function_name)
+            .await
+    }
+
+    // Build the query that calls the function with correctly typed NULL placeholders
+    fn build_query(signature: &FunctionSignature) -> String {
+        let uses_variadic = signature
+            .args
+            .iter()
+            .any(|arg| arg.mode == ArgMode::Variadic);
+
+        let args = signature
+            .args
+            .iter()
+            .filter(|arg| {
+                arg.mode.is_input() || (signature.is_procedure && arg.mode == ArgMode::Out)
+            })
+            .map(|arg| {
+                let placeholder = format!("NULL::{}", arg.arg_type);
+                match (&arg.name, &arg.mode) {
+                    (_, ArgMode::Variadic) => format!("VARIADIC {}", placeholder),
+                    (Some(name), _) if !uses_variadic => {
+                        format!("{} => {}", quote_ident(name), placeholder)
+                    }
+                    _ => placeholder,
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let call = format!(
+            "{}.{}({})",
+            quote_ident(&signature.schema),
+            quote_ident(&signature.name),
+            args
+        );
+
+        if signature.is_procedure {
+            return format!("CALL {};", call);
+        }
+
+        let has_output_args = signature.args.iter().any(|arg| arg.mode.is_output());
+        if signature.returns_set || has_output_args || !signature.composite_columns.is_empty() {
+            return format!("SELECT * FROM {};", call);
+        }
+
+        format!("SELECT {} AS {};", call, quote_ident(&signature.name))
+    }
+
+    fn yaml_quote(text: &str) -> String {
+        format!("'{}'", text.replace('\'', "''"))
+    }
+
+    pub fn build_test_file(signature: &FunctionSignature) -> String {
+        let mut file_content = format!(
+            "# Generated by tusk test --scaffold for {}.{}\n",
+            signature.schema, signature.overload_name
+        );
+
+        file_content.push_str(&format!(
+            "- name: {}\n",
+            Self::yaml_quote(&format!("{}: TODO describe this test", signature.overload_name))
+        ));
+        file_content.push_str(&format!(
+            "  query: {}\n",
+            Self::yaml_quote(&Self::build_query(signature))
+        ));
+
+        file_content.push_str("  expected_output:\n");
+        for (idx, column) in signature.output_columns().iter().enumerate() {
+            let prefix = if idx == 0 { "  - " } else { "    " };
+            file_content.push_str(&format!("{}{}: 'TODO'\n", prefix, column));
+        }
+
+        file_content
+    }
+
+    // Write a unit test file for each overload of the function given as schema.func
+    pub async fn scaffold(target: &str) -> Result<()> {
+        let (schema, function_name) = target
+            .split_once('.')
+            .context("The function to scaffold must be specified as schema.function_name")?;
+
+        println!("\nScaffolding unit tests for {}:", target.magenta());
+
+        let signatures = Self::get_signatures(schema, function_name).await?;
+        if signatures.is_empty() {
+            anyhow::bail!("The function {} could not be found locally or in the database", target);
+        }
+
+        for signature in signatures {
+            let file_path = format!(
+                "./schemas/{}/functions/{}/unit_tests/{}.yaml",
+                schema,
+                quote_ident(function_name),
+                signature.overload_name
+            );
+            let path = std::path::Path::new(&file_path);
+
+            if path.exists() {
+                println!(
+                    "\t{} ({}): Unit test file already exists and was not overwritten",
+                    "Warning".yellow(),
+                    file_path
+                );
+                continue;
+            }
+
+            tokio::fs::create_dir_all(path.parent().context("This file should have a parent directory")?).await?;
+            tokio::fs::write(path, Self::build_test_file(&signature)).await?;
+            println!("\tCreated {}", file_path.magenta());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::unit_test::test_config_manager::TestConfig;
+
+    #[test]
+    fn build_test_file_works() {
+        let definition = r#"CREATE OR REPLACE FUNCTION public.concatenating(var1 text, var2 text)
+ RETURNS text
+ LANGUAGE plpgsql
+AS $function$
+	BEGIN
+		return var1 || var2;
+	END
+$function$"#;
+
+        let signature =
+            FunctionSignature::from_definition("public", "concatenating(text, text)", definition)
+                .unwrap();
+
+        let test_file = TestScaffolder::build_test_file(&signature);
+        let parsed: Vec<TestConfig> =
+            serde_yaml::from_str(&test_file).expect("The scaffolded file should be valid yaml");
+
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(
+            parsed[0].query,
+            "SELECT public.concatenating(var1 => NULL::text, var2 => NULL::text) AS concatenating;"
+        );
+        assert_eq!(
+            parsed[0].expected_output.as_ref().unwrap()[0].keys().collect::<Vec<&String>>(),
+            vec!["concatenating"]
+        );
+    }
+
+    #[test]
+    fn build_query_for_procedures_and_sets_works() {
+        let definition = r#"CREATE OR REPLACE PROCEDURE public.insert_person(IN person_name text, VARIADIC tags text[])
+ LANGUAGE plpgsql
+AS $procedure$ BEGIN END $procedure$"#;
+
+        let signature =
+            FunctionSignature::from_definition("public", "insert_person(text, text[])", definition)
+                .unwrap();
+
+        assert_eq!(
+            TestScaffolder::build_query(&signature),
+            "CALL public.insert_person(NULL::text, VARIADIC NULL::text[]);"
+        );
+
+        let definition = r#"CREATE OR REPLACE FUNCTION public.get_people()
+ RETURNS TABLE(name text, age integer)
+ LANGUAGE sql
+AS $function$ SELECT name, age FROM people $function$"#;
+
+        let signature =
+            FunctionSignature::from_definition("public", "get_people()", definition).unwrap();
+
+        assert_eq!(
+            TestScaffolder::build_query(&signature),
+            "SELECT * FROM public.get_people();"
+        );
+    }
+}
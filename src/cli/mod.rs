@@ -16,7 +16,7 @@ pub enum Action {
     Init(Init),
 
     /// Pull the DDL from the database
-    Pull(Pull),
+    Pull(Box<Pull>),
 
     /// Push the changes in the local function DDL to the database
     Push(Push),
@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use regex::Regex;
 
 pub struct ChangeStatus {
     pub added: u32,
@@ -8,6 +9,121 @@ pub struct ChangeStatus {
     pub amount_before_change: u32,
 }
 
+#[derive(Debug, PartialEq)]
+enum RuleKind {
+    Include,
+    Exclude,
+}
+
+// A rule line within a config file. Rules are written as !glob or !/regex/ to exclude the matching
+// items, and as include: glob, include: /regex/ or /regex/ to include them. Globs support * and ?
+struct ConfigRule {
+    kind: RuleKind,
+    pattern: Regex,
+}
+
+impl ConfigRule {
+    fn parse(line: &str) -> Option<Result<ConfigRule>> {
+        let (kind, pattern) = if let Some(pattern) = line.strip_prefix('!') {
+            (RuleKind::Exclude, pattern)
+        } else if let Some(pattern) = line.strip_prefix("include:") {
+            (RuleKind::Include, pattern)
+        } else if is_regex(line) {
+            (RuleKind::Include, line)
+        } else {
+            return None;
+        };
+        let pattern = pattern.trim();
+
+        let regex = if is_regex(pattern) {
+            String::from(&pattern[1..pattern.len() - 1])
+        } else {
+            format!(
+                "^{}$",
+                regex::escape(pattern).replace("\\*", ".*").replace("\\?", ".")
+            )
+        };
+
+        Some(
+            Regex::new(&regex)
+                .with_context(|| format!("The rule {} is not a valid glob or regex", line))
+                .map(|pattern| ConfigRule { kind, pattern }),
+        )
+    }
+}
+
+fn is_regex(pattern: &str) -> bool {
+    pattern.len() > 2 && pattern.starts_with('/') && pattern.ends_with('/')
+}
+
+// Split a line of a config file into its contents and whether it is commented out
+fn strip_comment(line: &str) -> (&str, bool) {
+    let line = line.trim();
+    match line.strip_prefix("//").or_else(|| line.strip_prefix('#')) {
+        Some(contents) => (contents.trim(), true),
+        None => (line, false),
+    }
+}
+
+fn is_rule_line(line: &str) -> bool {
+    let (contents, _) = strip_comment(line);
+    contents.starts_with('!') || contents.starts_with("include:") || is_regex(contents)
+}
+
+// Get the rules that are uncommented in the config file
+fn get_rules(file_contents: &str) -> Result<Vec<ConfigRule>> {
+    file_contents
+        .lines()
+        .filter_map(|line| match strip_comment(line) {
+            (contents, false) => ConfigRule::parse(contents),
+            (_, true) => None,
+        })
+        .collect()
+}
+
+// Exclusions take precedence over inclusions. None is returned when no rule matches the item
+fn get_rule_kind(rules: &[ConfigRule], item: &str) -> Option<RuleKind> {
+    let matching = |kind: RuleKind| {
+        rules
+            .iter()
+            .any(|rule| rule.kind == kind && rule.pattern.is_match(item))
+    };
+
+    if matching(RuleKind::Exclude) {
+        Some(RuleKind::Exclude)
+    } else if matching(RuleKind::Include) {
+        Some(RuleKind::Include)
+    } else {
+        None
+    }
+}
+
+// Get the items of the config file with whether each one is tracked. An item is tracked when it is
+// uncommented unless a rule matches it, in which case the rule decides
+fn get_tracked_file_contents(file_path: &str) -> Result<Vec<(String, bool)>> {
+    let file_contents = std::fs::read_to_string(file_path)?;
+    let rules = get_rules(&file_contents)
+        .with_context(|| format!("The config file {} contains an invalid rule", file_path))?;
+
+    Ok(file_contents
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !is_rule_line(line))
+        .map(|line| {
+            let (contents, commented) = strip_comment(line);
+            let tracked = match get_rule_kind(&rules, contents) {
+                Some(RuleKind::Exclude) => false,
+                Some(RuleKind::Include) => true,
+                None => !commented,
+            };
+
+            match commented {
+                true => (String::from(contents), tracked),
+                false => (String::from(line), tracked),
+            }
+        })
+        .collect())
+}
+
 pub fn format_config_file(file_path: &str) -> Result<()> {
     let local_file_contents = std::fs::read_to_string(file_path)
         .unwrap_or_else(|_| panic!("The config file found at {} should exist", file_path));
@@ -24,6 +140,13 @@ fn format_config_file_contents(
     file_contents: &str,
     to_filter_out: &Option<&HashSet<&String>>,
 ) -> Vec<String> {
+    // Rule lines are kept at the top of the file in the order they were written
+    let mut rules: Vec<String> = file_contents
+        .lines()
+        .filter(|line| is_rule_line(line))
+        .map(|line| line.trim().to_owned())
+        .collect();
+
     let mut file_contents: Vec<String> = file_contents
         .trim()
         .lines()
@@ -31,7 +154,7 @@ fn format_config_file_contents(
             let db_item = (*line).trim().replace(' ', "");
 
             // Filter out empty lines
-            if db_item.is_empty() {
+            if db_item.is_empty() || is_rule_line(line) {
                 return None;
             }
 
@@ -47,37 +170,25 @@ fn format_config_file_contents(
 
     file_contents.sort_by_key(|val| val.replace("//", "").replace([' ', '#'], ""));
 
-    file_contents
+    rules.append(&mut file_contents);
+    rules
 }
 
+// Get the items that are tracked, ie. uncommented or included by a rule and not excluded by one
 pub fn get_uncommented_file_contents(file_path: &str) -> Result<Vec<String>> {
-    let result = std::fs::read_to_string(file_path)?
-        .lines()
-        .filter_map(|line| {
-            let line_trimmed = line.trim();
-            if line_trimmed.starts_with("//") || line_trimmed.starts_with('#') {
-                return None;
-            }
-            Some(String::from(line))
-        })
-        .collect::<Vec<String>>();
-
-    Ok(result)
+    Ok(get_tracked_file_contents(file_path)?
+        .into_iter()
+        .filter_map(|(item, tracked)| tracked.then_some(item))
+        .collect())
 }
 
+// Get the items that are not tracked, ie. commented out or excluded by a rule and not included by
+// one
 pub fn get_commented_file_contents(file_path: &str) -> Result<Vec<String>> {
-    let result = std::fs::read_to_string(file_path)?
-        .lines()
-        .filter_map(|line| {
-            let line = line.trim();
-            if line.starts_with("//") || line.starts_with('#') {
-                return Some(line.replace("//", "").replace('#', ""));
-            }
-            None
-        })
-        .collect::<Vec<String>>();
-
-    Ok(result)
+    Ok(get_tracked_file_contents(file_path)?
+        .into_iter()
+        .filter_map(|(item, tracked)| (!tracked).then_some(item))
+        .collect())
 }
 
 pub fn get_matching_file_contents<T, U, V>(
@@ -122,8 +233,12 @@ pub fn update_file_contents_from_db(
     let mut local_file_contents = std::fs::read_to_string(file_path)
         .unwrap_or_else(|_| panic!("The config file found at {} should exist", file_path));
 
+    let rules = get_rules(&local_file_contents)
+        .with_context(|| format!("The config file {} contains an invalid rule", file_path))?;
+
     let all_local_contents: HashSet<String> = local_file_contents
         .lines()
+        .filter(|line| !is_rule_line(line))
         .map(|line| line.replace("//", "").replace([' ', '#'], ""))
         .collect();
 
@@ -132,7 +247,13 @@ pub fn update_file_contents_from_db(
 
     let mut added = 0;
     not_in_local.into_iter().for_each(|item| {
-        if add_new_as_commented {
+        let add_as_commented = match get_rule_kind(&rules, item) {
+            Some(RuleKind::Exclude) => true,
+            Some(RuleKind::Include) => false,
+            None => add_new_as_commented,
+        };
+
+        if add_as_commented {
             local_file_contents.push_str(&(String::from("\n//") + item));
         } else {
             local_file_contents.push_str(&(String::from("\n") + item));
@@ -197,6 +318,18 @@ mod tests {
                 ]
             )
         }
+
+        #[test]
+        fn format_config_file_contents_keeps_rules_at_top() {
+            let file_contents = "b_item\n!tmp_*\n//a_item\ninclude: report_*\n//!/^old_/";
+
+            let result = format_config_file_contents(file_contents, &None);
+
+            assert_eq!(
+                result,
+                vec!["!tmp_*", "include: report_*", "//!/^old_/", "//a_item", "b_item"]
+            )
+        }
     }
 
     mod getting_file_contents {
@@ -251,9 +384,69 @@ mod tests {
             );
         }
 
+        #[test]
+        fn file_contents_apply_rules() {
+            let temp_test_dir =
+                tempdir_in(".").expect("Temporary Directory should not fail to be created");
+            let file_path = String::from(
+                temp_test_dir
+                    .path()
+                    .join("test_config.txt")
+                    .to_str()
+                    .unwrap(),
+            );
+
+            std::fs::write(&file_path, "!tmp_*\ninclude: report_?\n/_archive$/\n//!people\npeople\ntmp_one\n//report_a\n//report_ab\n//sales_archive\n//tmp_archive").unwrap();
+
+            assert_eq!(
+                get_uncommented_file_contents(&file_path)
+                    .expect("This should never fail in this scenario"),
+                vec!["people", "report_a", "sales_archive"]
+            );
+
+            assert_eq!(
+                get_commented_file_contents(&file_path)
+                    .expect("This should never fail in this scenario"),
+                vec!["tmp_one", "report_ab", "tmp_archive"]
+            );
+
+            std::fs::write(&file_path, "!/[/\npeople").unwrap();
+            assert!(get_uncommented_file_contents(&file_path).is_err());
+        }
+
+        #[test]
+        fn update_file_contents_from_db_applies_rules() {
+            let temp_test_dir =
+                tempdir_in(".").expect("Temporary Directory should not fail to be created");
+            let file_path = String::from(
+                temp_test_dir
+                    .path()
+                    .join("test_config.txt")
+                    .to_str()
+                    .unwrap(),
+            );
+
+            std::fs::write(&file_path, "!tmp_*\ninclude: report_*\npeople\nremoved").unwrap();
+
+            let from_db: HashSet<String> = ["people", "tmp_one", "report_a", "orders"]
+                .into_iter()
+                .map(String::from)
+                .collect();
+
+            let change_status = update_file_contents_from_db(&file_path, from_db, true, true)
+                .expect("This should never fail in this scenario");
+
+            assert_eq!(change_status.added, 3);
+            assert_eq!(change_status.removed, 1);
+            assert_eq!(
+                std::fs::read_to_string(&file_path).unwrap(),
+                "!tmp_*\ninclude: report_*\n//orders\npeople\nreport_a\n//tmp_one"
+            );
+        }
+
         #[test]
         fn get_matching_file_contents_works() {
-            let test_uncommented_contents = vec![
+            let test_uncommented_contents = [
                 String::from("Test_One"),
                 String::from("Test_Two"),
                 String::from("unrelated"),
@@ -12,6 +12,8 @@ static USER_CONFIG: OnceLock<UserConfig> = OnceLock::new();
 pub struct FetchOptions {
     pub new_items_commented: HashMap<String, bool>,
     pub delete_items_from_config: bool,
+    #[serde(default)] // Allow config files created before partitions were collapsed into their parent
+    pub include_partitions: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -19,12 +21,37 @@ pub struct PullOptions {
     pub clean_ddl_before_pulling: bool,
     pub pg_dump_additional_args: Vec<String>,
     pub confirm_before_pull: bool,
+    #[serde(default)] // Allow config files created before sequences were tracked
+    pub include_sequence_values: bool,
+    #[serde(default)] // Allow config files created before indexes were tracked
+    pub exclude_indexes_from_table_ddl: bool,
+    #[serde(default)] // Allow config files created before triggers were left out of table ddl
+    pub exclude_triggers_from_table_ddl: bool,
+    #[serde(default)] // Allow config files created before partition summaries existed
+    pub include_partition_summary: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PushOptions {
     pub test_after_push: bool,
     pub confirm_before_push: bool,
+    #[serde(default)] // Allow config files created before comments could be synced
+    pub sync_doc_comments: bool,
+}
+
+// Where the doc comments of functions are written
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DocCommentStyle {
+    #[default]
+    Block, // A /** */ block comment within the function
+    Line, // Consecutive -- line comments within the function
+    CommentOn, // The COMMENT ON FUNCTION text on the database
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DocOptions {
+    pub comment_style: DocCommentStyle,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -32,6 +59,8 @@ pub struct UserConfig {
     pub fetch_options: FetchOptions,
     pub pull_options: PullOptions,
     pub push_options: PushOptions,
+    #[serde(default)] // Allow config files created before doc options existed
+    pub doc_options: DocOptions,
 }
 
 impl UserConfig {
@@ -45,9 +74,9 @@ impl UserConfig {
     }
 
     pub fn get_global() -> Result<&'static UserConfig> {
-        return USER_CONFIG
+        USER_CONFIG
             .get()
-            .context("User Config must be set before this variable can be used");
+            .context("User Config must be set before this variable can be used")
     }
 
     pub fn user_confirmed<T,U,V>(
@@ -111,6 +140,10 @@ pull_options:
 push_options:
     test_after_push: true
     confirm_before_push: true # Require confirmation of what functions will be pushed before commencing pushing 
+    sync_doc_comments: true
+
+doc_options:
+    comment_style: comment_on
         "#;
 
         let parsed: UserConfig = serde_yaml::from_str(test_yaml).expect("This should never fail");
@@ -126,10 +159,15 @@ push_options:
                     ("data_types".to_string(), false),
                 ]),
                 delete_items_from_config: true,
+                include_partitions: false,
             },
             pull_options: PullOptions {
                 clean_ddl_before_pulling: true,
                 confirm_before_pull: true,
+                include_sequence_values: false,
+                exclude_indexes_from_table_ddl: false,
+                exclude_triggers_from_table_ddl: false,
+                include_partition_summary: false,
                 pg_dump_additional_args: vec![
                     "--no-privileges".to_string(),
                     "--no-tablespaces".to_string(),
@@ -138,9 +176,21 @@ push_options:
             push_options: PushOptions {
                 test_after_push: true,
                 confirm_before_push: true,
+                sync_doc_comments: true,
+            },
+            doc_options: DocOptions {
+                comment_style: DocCommentStyle::CommentOn,
             },
         };
 
         assert!(parsed == expected);
+
+        // Config files written before the doc options existed should still parse
+        let test_yaml = test_yaml.replace("comment_style: comment_on", "");
+        let test_yaml = test_yaml.replace("doc_options:", "");
+        let test_yaml = test_yaml.replace("sync_doc_comments: true", "");
+        let parsed: UserConfig = serde_yaml::from_str(&test_yaml).expect("This should never fail");
+        assert_eq!(parsed.doc_options.comment_style, DocCommentStyle::Block);
+        assert!(!parsed.push_options.sync_doc_comments);
     }
 }
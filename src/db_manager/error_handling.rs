@@ -2,7 +2,7 @@ use colored::Colorize;
 use sqlx::{postgres::PgDatabaseError, Error};
 
 pub fn get_db_error(e: Error) -> String {
-    return match e {
+    match e {
         sqlx::Error::Database(e) => match e.try_downcast::<PgDatabaseError>() {
             Ok(e) => {
                 let message = e.message();
@@ -31,5 +31,5 @@ pub fn get_db_error(e: Error) -> String {
             Err(e) => format!("{}: {}", "Error".red(), e),
         },
         _ => format!("{}: An unexpected error occured", "Error".red()),
-    };
+    }
 }
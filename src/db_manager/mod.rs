@@ -90,8 +90,17 @@ struct DbEnvVars {
     db_name: String,
 }
 
+impl DbEnvVars {
+    fn get_connection_string(&self, db_name: &str) -> String {
+        format!(
+            "postgres://{}:{}@{}:{}/{}",
+            self.db_user, self.db_pass, self.db_host, self.db_port, db_name
+        )
+    }
+}
+
 pub struct DbConnection {
-    _env_vars: DbEnvVars,
+    env_vars: DbEnvVars,
     pool: PgPool,
     connection_string: String,
     _ssh_connection: Option<SSHConnection>,
@@ -100,17 +109,10 @@ pub struct DbConnection {
 
 impl DbConnection {
     pub async fn new() -> Result<Self> {
-        let (_env_vars, _ssh_connection) = Self::get_db_env_vars()?;
+        let (env_vars, _ssh_connection) = Self::get_db_env_vars()?;
 
         let pg_bin_path = dotenvy::var("PG_BIN_PATH").unwrap_or_else(|_| String::from("pg_dump"));
-        let connection_string = format!(
-            "postgres://{}:{}@{}:{}/{}",
-            _env_vars.db_user,
-            _env_vars.db_pass,
-            _env_vars.db_host,
-            _env_vars.db_port,
-            _env_vars.db_name
-        );
+        let connection_string = env_vars.get_connection_string(&env_vars.db_name);
 
         let pool = PgPoolOptions::new()
             .max_connections(MAX_DB_CONNECTIONS)
@@ -118,7 +120,7 @@ impl DbConnection {
             .await?;
 
         Ok(DbConnection {
-            _env_vars,
+            env_vars,
             pool,
             connection_string,
             _ssh_connection,
@@ -130,6 +132,11 @@ impl DbConnection {
         &self.connection_string
     }
 
+    // Get the connection string for a different database on the same server
+    pub fn get_connection_string_for_db(&self, db_name: &str) -> String {
+        self.env_vars.get_connection_string(db_name)
+    }
+
     pub fn get_pg_bin_path(&self) -> &str {
         &self.pg_bin_path
     }